@@ -0,0 +1,142 @@
+use aoc2025::day1::{Dial, count_clicks, count_clicks_parallel};
+use aoc2025::day2::{Id, Radix, RepetitionPolicy, find_all_invalid_ids, sum_invalid_ids};
+use aoc2025::day3::max_joltage;
+use aoc2025::day4::Grid;
+use aoc2025::day5::simplify_ranges;
+use aoc2025::synthetic::{self, Rng};
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use std::hint::black_box;
+
+// Fixed so runs are comparable with each other
+const SEED: u64 = 2025;
+
+fn dial_apply(c: &mut Criterion) {
+    let mut group = c.benchmark_group("day1/Dial::apply");
+
+    for count in [1_000, 10_000, 100_000] {
+        let turns = synthetic::turns(&mut Rng::new(SEED), count, 1000);
+
+        group.throughput(Throughput::Elements(count as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(count), &turns, |b, turns| {
+            b.iter(|| {
+                turns.iter().fold((Dial::new(), 0), |(dial, clicks), turn| {
+                    let result = dial.apply(*turn);
+                    (result.dial, clicks + result.clicks)
+                })
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn clicks(c: &mut Criterion) {
+    let mut group = c.benchmark_group("day1/count_clicks");
+    let turns = synthetic::turns(&mut Rng::new(SEED), 1_000_000, 1000);
+
+    group.throughput(Throughput::Elements(turns.len() as u64));
+    group.bench_function("sequential", |b| {
+        b.iter(|| count_clicks(Dial::new(), &turns))
+    });
+    for threads in [2, 4, 8] {
+        group.bench_with_input(
+            BenchmarkId::new("parallel", threads),
+            &threads,
+            |b, threads| b.iter(|| count_clicks_parallel(Dial::new(), &turns, *threads)),
+        );
+    }
+
+    group.finish();
+}
+
+fn find_invalid_ids(c: &mut Criterion) {
+    let mut group = c.benchmark_group("day2/find_all_invalid_ids");
+
+    for (digits, width) in [(6, 1_000), (10, 1_000_000), (15, 1_000_000_000)] {
+        let range = synthetic::id_range(&mut Rng::new(SEED), digits, width);
+
+        group.bench_with_input(BenchmarkId::from_parameter(width), &range, |b, range| {
+            b.iter(|| find_all_invalid_ids(range).sum::<Id>())
+        });
+    }
+
+    group.finish();
+
+    let mut group = c.benchmark_group("day2/sum_invalid_ids");
+
+    for digits in [6, 18, 36] {
+        let range = 1..=Id::pow(10, digits);
+
+        group.bench_with_input(BenchmarkId::from_parameter(digits), &range, |b, range| {
+            b.iter(|| sum_invalid_ids(range, &RepetitionPolicy::Exactly(2), Radix::DECIMAL))
+        });
+    }
+
+    group.finish();
+}
+
+fn joltage(c: &mut Criterion) {
+    let mut group = c.benchmark_group("day3/max_joltage");
+
+    for length in [2, 12] {
+        for size in [100, 1_000, 10_000] {
+            let bank = synthetic::bank(&mut Rng::new(SEED), size);
+
+            group.throughput(Throughput::Elements(size as u64));
+            group.bench_with_input(
+                BenchmarkId::new(format!("length {}", length), size),
+                &bank,
+                |b, bank| b.iter(|| max_joltage(bank, black_box(length))),
+            );
+        }
+    }
+
+    group.finish();
+}
+
+fn remove_rolls(c: &mut Criterion) {
+    let mut group = c.benchmark_group("day4/Grid::remove_accessible_repeated");
+
+    for size in [10, 100, 1_000] {
+        let grid: Grid = synthetic::grid(&mut Rng::new(SEED), size, size, 60)
+            .parse()
+            .unwrap();
+
+        group.throughput(Throughput::Elements((size * size) as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &grid, |b, grid| {
+            b.iter_batched(
+                || grid.clone(),
+                |mut grid| grid.remove_accessible_repeated(),
+                criterion::BatchSize::LargeInput,
+            )
+        });
+    }
+
+    group.finish();
+}
+
+fn simplify(c: &mut Criterion) {
+    let mut group = c.benchmark_group("day5/simplify_ranges");
+
+    for count in [10, 100, 1_000] {
+        let ranges = synthetic::ranges(&mut Rng::new(SEED), count, 1 << 40, 1 << 34);
+
+        group.throughput(Throughput::Elements(count as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(count), &ranges, |b, ranges| {
+            b.iter(|| simplify_ranges(ranges))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    dial_apply,
+    clicks,
+    find_invalid_ids,
+    joltage,
+    remove_rolls,
+    simplify
+);
+criterion_main!(benches);
@@ -0,0 +1,225 @@
+use crate::error::{Error, Result};
+use crate::runner::format_rows;
+use crate::solution::{Answer, Puzzle};
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BenchConfig {
+    /// Runs thrown away before timing starts, to warm caches and the like
+    pub warmup: usize,
+    pub runs: usize,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        BenchConfig {
+            warmup: 3,
+            runs: 20,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub p95: Duration,
+}
+
+impl Stats {
+    pub fn of(samples: &[Duration]) -> Stats {
+        assert!(!samples.is_empty(), "no samples to summarise");
+
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+
+        let n = sorted.len();
+        let median = if n % 2 == 1 {
+            sorted[n / 2]
+        } else {
+            (sorted[n / 2 - 1] + sorted[n / 2]) / 2
+        };
+        let mean = sorted.iter().sum::<Duration>() / n as u32;
+        // Nearest rank, so with fewer than 20 samples this is just the slowest
+        let p95 = sorted[(n * 95).div_ceil(100) - 1];
+
+        Stats {
+            min: sorted[0],
+            median,
+            mean,
+            p95,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BenchReport {
+    pub day: u32,
+    pub part: u32,
+    pub answer: String,
+    pub parse: Stats,
+    pub solve: Stats,
+}
+
+/// Every run parses the input afresh so parsing gets timed as often as solving does.
+pub fn bench<P: Puzzle>(part: u32, input: &str, config: &BenchConfig) -> Result<BenchReport> {
+    let solve: fn(&P::Input) -> Result<Answer> = match part {
+        1 => <P as Puzzle>::part1,
+        2 => <P as Puzzle>::part2,
+        _ => return Err(Error::Usage(format!("Day {} has no part {}", P::DAY, part))),
+    };
+
+    if config.runs == 0 {
+        return Err(Error::Usage(
+            "Benchmarking needs at least one run".to_string(),
+        ));
+    }
+
+    for _ in 0..config.warmup {
+        solve(&P::parse(input)?)?;
+    }
+
+    let mut parse_times = Vec::with_capacity(config.runs);
+    let mut solve_times = Vec::with_capacity(config.runs);
+    let mut answer = String::new();
+
+    for _ in 0..config.runs {
+        let start = Instant::now();
+        let parsed = P::parse(input)?;
+        let parsed_at = Instant::now();
+        let result = solve(&parsed)?;
+        let solved_at = Instant::now();
+
+        parse_times.push(parsed_at - start);
+        solve_times.push(solved_at - parsed_at);
+        answer = result.to_string();
+    }
+
+    Ok(BenchReport {
+        day: P::DAY,
+        part,
+        answer,
+        parse: Stats::of(&parse_times),
+        solve: Stats::of(&solve_times),
+    })
+}
+
+pub fn format_reports(reports: &[BenchReport]) -> String {
+    let rows: Vec<[String; 8]> = reports
+        .iter()
+        .flat_map(|r| {
+            [("parse", r.parse), ("solve", r.solve)].map(|(phase, stats)| {
+                [
+                    r.day.to_string(),
+                    r.part.to_string(),
+                    phase.to_string(),
+                    format!("{:.2?}", stats.min),
+                    format!("{:.2?}", stats.median),
+                    format!("{:.2?}", stats.mean),
+                    format!("{:.2?}", stats.p95),
+                    r.answer.clone(),
+                ]
+            })
+        })
+        .collect();
+
+    format_rows(
+        [
+            "Day", "Part", "Phase", "Min", "Median", "Mean", "P95", "Answer",
+        ],
+        &rows,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bench::*;
+
+    struct Doubler;
+
+    impl Puzzle for Doubler {
+        const DAY: u32 = 99;
+
+        type Input = u64;
+
+        fn parse(input: &str) -> Result<u64> {
+            input
+                .trim()
+                .parse()
+                .map_err(|_| Error::InvalidPuzzle(input.to_string()))
+        }
+
+        fn part1(input: &u64) -> Result<Answer> {
+            Ok(Box::new(input * 2))
+        }
+
+        fn part2(_: &u64) -> Result<Answer> {
+            Err(Error::InvalidPuzzle("no part 2".to_string()))
+        }
+    }
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    #[test]
+    fn stats_odd_number_of_samples() {
+        assert_eq!(
+            Stats::of(&[ms(5), ms(1), ms(3)]),
+            Stats {
+                min: ms(1),
+                median: ms(3),
+                mean: ms(3),
+                p95: ms(5),
+            }
+        );
+    }
+
+    #[test]
+    fn stats_even_number_of_samples() {
+        let samples: Vec<Duration> = (1..=20).map(ms).collect();
+
+        assert_eq!(
+            Stats::of(&samples),
+            Stats {
+                min: ms(1),
+                median: Duration::from_micros(10_500),
+                mean: Duration::from_micros(10_500),
+                p95: ms(19),
+            }
+        );
+    }
+
+    #[test]
+    fn bench_reports_answer() {
+        let config = BenchConfig { warmup: 1, runs: 3 };
+        let report = bench::<Doubler>(1, "21\n", &config).unwrap();
+
+        assert_eq!(report.day, 99);
+        assert_eq!(report.part, 1);
+        assert_eq!(report.answer, "42");
+    }
+
+    #[test]
+    fn bench_errors() {
+        let config = BenchConfig::default();
+
+        assert!(matches!(
+            bench::<Doubler>(3, "21", &config),
+            Err(Error::Usage(_))
+        ));
+        assert!(matches!(
+            bench::<Doubler>(2, "21", &config),
+            Err(Error::InvalidPuzzle(_))
+        ));
+        assert!(matches!(
+            bench::<Doubler>(1, "x", &config),
+            Err(Error::InvalidPuzzle(_))
+        ));
+        assert!(matches!(
+            bench::<Doubler>(1, "21", &BenchConfig { warmup: 0, runs: 0 }),
+            Err(Error::Usage(_))
+        ));
+    }
+}
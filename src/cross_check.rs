@@ -0,0 +1,255 @@
+//! Differential testing: a day can pair a fast implementation with a slow one that's obviously
+//! right, and run both over its real input and a pile of made-up ones until they disagree.
+
+use crate::error::Result;
+use crate::synthetic::Rng;
+use crate::{day1, day2};
+use std::fmt::{Debug, Display, Formatter};
+
+/// What each day implements for every pair of implementations it wants compared
+pub trait CrossCheck: Sync {
+    const DAY: u32;
+
+    const NAME: &str;
+
+    type Input: Debug;
+
+    type Output: PartialEq + Debug;
+
+    /// The inputs to compare on from a real puzzle input
+    fn real_inputs(input: &str) -> Result<Vec<Self::Input>>;
+
+    /// Keep these small enough that the reference implementation finishes quickly
+    fn random_input(rng: &mut Rng) -> Self::Input;
+
+    fn reference(input: &Self::Input) -> Self::Output;
+
+    fn optimised(input: &Self::Input) -> Self::Output;
+}
+
+/// A [CrossCheck] with the types erased so different days can sit together in [CROSS_CHECKS]
+pub trait Check: Sync {
+    fn day(&self) -> u32;
+
+    fn name(&self) -> String;
+
+    /// Compares on everything in `input` first, then `random` made-up inputs, stopping at the
+    /// first difference
+    fn run(&self, input: Option<&str>, random: usize, rng: &mut Rng) -> Result<CheckReport>;
+}
+
+impl<C: CrossCheck> Check for C {
+    fn day(&self) -> u32 {
+        C::DAY
+    }
+
+    fn name(&self) -> String {
+        format!("day{}/{}", C::DAY, C::NAME)
+    }
+
+    fn run(&self, input: Option<&str>, random: usize, rng: &mut Rng) -> Result<CheckReport> {
+        let real = match input {
+            Some(input) => C::real_inputs(input)?,
+            None => vec![],
+        };
+        let mut report = CheckReport {
+            name: self.name(),
+            real: 0,
+            random: 0,
+            divergence: None,
+        };
+
+        for input in &real {
+            report.real += 1;
+            if let Some(divergence) = compare::<C>(input) {
+                report.divergence = Some(divergence);
+                return Ok(report);
+            }
+        }
+
+        for _ in 0..random {
+            report.random += 1;
+            if let Some(divergence) = compare::<C>(&C::random_input(rng)) {
+                report.divergence = Some(divergence);
+                return Ok(report);
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+fn compare<C: CrossCheck>(input: &C::Input) -> Option<Divergence> {
+    let reference = C::reference(input);
+    let optimised = C::optimised(input);
+
+    (reference != optimised).then(|| Divergence {
+        input: format!("{:?}", input),
+        reference: format!("{:?}", reference),
+        optimised: format!("{:?}", optimised),
+    })
+}
+
+/// The first input the two implementations disagreed on, and what each said
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Divergence {
+    pub input: String,
+    pub reference: String,
+    pub optimised: String,
+}
+
+/// How many inputs a check got through, and where it stopped if they disagreed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CheckReport {
+    pub name: String,
+    pub real: usize,
+    pub random: usize,
+    pub divergence: Option<Divergence>,
+}
+
+impl CheckReport {
+    pub fn passed(&self) -> bool {
+        self.divergence.is_none()
+    }
+}
+
+impl Display for CheckReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.divergence {
+            None => write!(
+                f,
+                "{}: {} real and {} random inputs agree",
+                self.name, self.real, self.random
+            ),
+            Some(divergence) => write!(
+                f,
+                "{}: diverged on {} input {}\n  reference: {}\n  optimised: {}",
+                self.name,
+                if self.random > 0 { "random" } else { "real" },
+                divergence.input,
+                divergence.reference,
+                divergence.optimised
+            ),
+        }
+    }
+}
+
+/// How many made-up inputs `--cross-check` tries after the real ones
+pub const RANDOM_INPUTS: usize = 1000;
+
+// Same deal as SOLUTIONS, a new check goes here
+pub static CROSS_CHECKS: &[&dyn Check] = &[&day1::ParallelClicks, &day2::GeneratedIds];
+
+pub fn for_day(day: u32) -> impl Iterator<Item = &'static dyn Check> {
+    CROSS_CHECKS.iter().copied().filter(move |c| c.day() == day)
+}
+
+/// Runs every check registered for each day over the day's input, which is read already as stdin can
+/// only be read the once
+pub fn run_all(inputs: &[(u32, &str)], random: usize, rng: &mut Rng) -> Result<Vec<CheckReport>> {
+    inputs
+        .iter()
+        .flat_map(|(day, input)| for_day(*day).map(move |check| (check, *input)))
+        .map(|(check, input)| check.run(Some(input), random, rng))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cross_check::*;
+
+    struct Squares;
+
+    impl CrossCheck for Squares {
+        const DAY: u32 = 0;
+        const NAME: &str = "squares";
+
+        type Input = u64;
+        type Output = u64;
+
+        fn real_inputs(input: &str) -> Result<Vec<u64>> {
+            Ok(input.split(',').map(|n| n.parse().unwrap()).collect())
+        }
+
+        fn random_input(rng: &mut Rng) -> u64 {
+            rng.in_range(0..=100)
+        }
+
+        fn reference(n: &u64) -> u64 {
+            (0..*n).map(|_| n).sum()
+        }
+
+        // Wrong for exactly one input
+        fn optimised(n: &u64) -> u64 {
+            if *n == 37 { 0 } else { n * n }
+        }
+    }
+
+    #[test]
+    fn reports_first_divergence() {
+        let report = Squares.run(Some("3,37,5"), 10, &mut Rng::new(0)).unwrap();
+
+        assert_eq!(report.name, "day0/squares");
+        assert_eq!((report.real, report.random), (2, 0));
+        assert_eq!(
+            report.divergence,
+            Some(Divergence {
+                input: "37".to_string(),
+                reference: "1369".to_string(),
+                optimised: "0".to_string(),
+            })
+        );
+        assert!(report.to_string().contains("diverged on real input 37"));
+    }
+
+    #[test]
+    fn random_inputs_after_real_ones() {
+        let report = Squares.run(Some("3,5"), 1000, &mut Rng::new(0)).unwrap();
+
+        assert_eq!(report.real, 2);
+        assert!(report.random > 0 && report.random < 1000);
+        assert_eq!(report.divergence.unwrap().input, "37");
+
+        let report = Squares.run(None, 0, &mut Rng::new(0)).unwrap();
+        assert!(report.passed());
+        assert_eq!(
+            report.to_string(),
+            "day0/squares: 0 real and 0 random inputs agree"
+        );
+    }
+
+    #[test]
+    fn run_all_uses_the_text_given() {
+        let reports = run_all(
+            &[
+                (1, "L68\nL30\nR48"),
+                (2, "11-22,95-115"),
+                (3, "987654321111111"),
+            ],
+            5,
+            &mut Rng::new(0),
+        )
+        .unwrap();
+
+        assert_eq!(
+            reports
+                .iter()
+                .map(|r| (r.name.as_str(), r.real, r.random, r.passed()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("day1/parallel-clicks", 1, 5, true),
+                ("day2/generated-ids", 4, 5, true),
+            ]
+        );
+    }
+
+    #[test]
+    fn registered_checks_agree() {
+        let mut rng = Rng::new(2025);
+
+        for check in CROSS_CHECKS {
+            let report = check.run(None, 200, &mut rng).unwrap();
+            assert!(report.passed(), "{}", report);
+        }
+    }
+}
@@ -1,96 +1,194 @@
-use regex::Regex;
-use std::error::Error;
-use std::fs::File;
-use std::io;
+use crate::cross_check::CrossCheck;
+use crate::error::{Error, ParseError, Result};
+use crate::runner::format_rows;
+use crate::solution::{Answer, Puzzle};
+use crate::synthetic::{self, Rng};
+use std::collections::BTreeMap;
+use std::fmt;
 use std::io::BufRead;
-use std::path::Path;
 use std::str::FromStr;
-use std::sync::LazyLock;
+use std::thread;
 
 // Maybe this would be better with unsigned arithmetic but I do not trust myself with it
 
 // This is waaaay more accuracy than we need but hey, in for a penny
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
-struct Dial(i64);
+pub struct Dial {
+    direction: i64,
+    size: i64,
+}
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
-struct Turn(i64);
+pub struct Turn(pub i64);
+
+/// A single turn's clicks fit in a u64 but adding up enough of the biggest turns on a one position
+/// dial doesn't, whereas there can't be enough turns in memory to fill a u128
+pub type Clicks = u128;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
-struct TurnResult {
-    dial: Dial,
-    clicks: i64,
+pub struct TurnResult {
+    pub dial: Dial,
+    pub clicks: Clicks,
 }
 
 impl Dial {
-    fn new() -> Dial {
-        Dial(50)
+    /// Anything bigger could overflow when working out where a turn lands
+    pub const MAX_SIZE: i64 = i64::MAX / 2;
+
+    /// The puzzle's dial, 100 positions starting at 50
+    pub fn new() -> Dial {
+        Dial::of(50)
     }
 
-    fn direction(&self) -> i64 {
-        self.0
+    pub fn direction(&self) -> i64 {
+        self.direction
     }
 
-    fn of(direction: i64) -> Dial {
+    pub fn size(&self) -> i64 {
+        self.size
+    }
+
+    /// A direction on the puzzle's 100 position dial
+    pub fn of(direction: i64) -> Dial {
+        Dial::sized(100, direction)
+    }
+
+    pub fn sized(size: i64, direction: i64) -> Dial {
+        assert!(
+            (1..=Dial::MAX_SIZE).contains(&size),
+            "invalid dial size: {}",
+            size
+        );
         assert!(
-            (0..100).contains(&direction),
+            (0..size).contains(&direction),
             "invalid direction: {}",
             direction
         );
-        Dial(direction)
+        Dial { direction, size }
     }
 
-    fn apply(&self, turn: Turn) -> TurnResult {
-        let result = (self.0 + turn.0).rem_euclid(100);
-
+    pub fn apply(&self, turn: Turn) -> TurnResult {
         // Complete turns are always a click
-        let mut clicks = turn.0.abs() / 100;
+        let (mut clicks, partial) = turn.split(self.size);
 
-        if self.0 > 0 {
-            // If we travel _through_ zero (not from it!) we may incur another click
-            let deflection = (turn.0.abs() % 100) * turn.0.signum();
-            let destination = self.0 + deflection;
+        // Whatever is left over is less than a full turn so can't overflow when added
+        let deflection = partial * turn.0.signum();
+        let destination = self.direction + deflection;
 
-            // If it ends up on zero or beyond it, we get an additional click
-            if destination <= 0 || destination >= 100 {
-                clicks += 1;
-            }
+        // If we travel _through_ zero (not from it!) we may incur another click, if it ends up on
+        // zero or beyond it
+        if self.direction > 0 && (destination <= 0 || destination >= self.size) {
+            clicks += 1;
         }
 
-        TurnResult::new(Dial::of(result), clicks)
+        TurnResult::new(
+            Dial::sized(self.size, destination.rem_euclid(self.size)),
+            clicks as Clicks,
+        )
+    }
+}
+
+impl Default for Dial {
+    fn default() -> Self {
+        Dial::new()
     }
 }
 
 impl Turn {
-    fn parse(s: &str) -> Turn {
-        static RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^([LR])([0-9]+)$").unwrap());
+    /// Accepts `L68`/`R48` in either case, optionally with a space before the amount, or just a
+    /// signed amount like `-68`/`+48`/`48` with left being negative. Surrounding whitespace is
+    /// ignored.
+    pub fn parse(s: &str) -> Result<Turn, ParseError> {
+        let chars: Vec<char> = s.chars().collect();
+        let skip_whitespace = |mut i: usize| {
+            while chars.get(i).is_some_and(|c| c.is_whitespace()) {
+                i += 1;
+            }
+            i
+        };
+        // Columns point at the first character that can't be part of a turn
+        let expected_turn = |i: usize| {
+            ParseError::new(
+                1,
+                i + 1,
+                format!("expected a turn like L68 or -68, found {:?}", s),
+            )
+        };
 
-        let captures = RE.captures(s).unwrap();
+        let start = skip_whitespace(0);
+        let (dir, mut i) = match chars.get(start) {
+            Some('L' | 'l') => (-1, skip_whitespace(start + 1)),
+            Some('R' | 'r') => (1, skip_whitespace(start + 1)),
+            Some('-') => (-1, start + 1),
+            Some('+') => (1, start + 1),
+            _ => (1, start),
+        };
 
-        let dir = if "L" == &captures[1] { -1 } else { 1 };
-        let amount = i64::from_str(&captures[2]).unwrap();
+        let digits_start = i;
+        while chars.get(i).is_some_and(|c| c.is_ascii_digit()) {
+            i += 1;
+        }
+        let digits: String = chars[digits_start..i].iter().collect();
 
-        Turn(dir * amount)
+        if digits.is_empty() {
+            return Err(expected_turn(i));
+        }
+        let end = skip_whitespace(i);
+        if end < chars.len() {
+            return Err(expected_turn(end));
+        }
+
+        // The magnitude's unsigned as the biggest left turn is one further than the biggest right
+        let invalid_amount = |e: &dyn fmt::Display| {
+            ParseError::new(1, digits_start + 1, format!("invalid turn amount: {}", e))
+        };
+        let magnitude = u64::from_str(&digits).map_err(|e| invalid_amount(&e))?;
+        let amount = if dir < 0 {
+            0i64.checked_sub_unsigned(magnitude)
+        } else {
+            i64::try_from(magnitude).ok()
+        };
+
+        amount
+            .map(Turn)
+            .ok_or_else(|| invalid_amount(&"number too large to fit in target type"))
+    }
+
+    /// How many complete turns of a dial this is and how far it goes beyond them. That's worked
+    /// out unsigned as negating the biggest left turn would overflow, and a single position dial
+    /// goes round one more time than an i64 could count.
+    fn split(&self, size: i64) -> (u64, i64) {
+        let distance = self.0.unsigned_abs();
+        let size = size as u64;
+
+        (distance / size, (distance % size) as i64)
+    }
+}
+
+impl fmt::Display for Turn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let dir = if self.0 < 0 { 'L' } else { 'R' };
+        write!(f, "{}{}", dir, self.0.unsigned_abs())
     }
 }
 
 impl TurnResult {
-    fn new(dial: Dial, clicks: i64) -> Self {
+    pub fn new(dial: Dial, clicks: Clicks) -> Self {
         TurnResult { dial, clicks }
     }
 
     #[cfg(test)] // Not test-specific but unused elsewhere
-    fn of(dial_direction: i64, clicks: i64) -> Self {
+    fn of(dial_direction: i64, clicks: Clicks) -> Self {
         Self::new(Dial::of(dial_direction), clicks)
     }
 }
 
-fn count_zeroes(initial: Dial, turns: Vec<Turn>) -> i64 {
+pub fn count_zeroes(initial: Dial, turns: &[Turn]) -> u64 {
     let mut dial = initial;
-    let mut count: i64 = 0;
+    let mut count: u64 = 0;
 
     for turn in turns {
-        dial = dial.apply(turn).dial;
+        dial = dial.apply(*turn).dial;
 
         if dial.direction() == 0 {
             count += 1
@@ -100,12 +198,12 @@ fn count_zeroes(initial: Dial, turns: Vec<Turn>) -> i64 {
     count
 }
 
-fn count_clicks(initial: Dial, turns: Vec<Turn>) -> i64 {
+pub fn count_clicks(initial: Dial, turns: &[Turn]) -> Clicks {
     let mut dial = initial;
-    let mut clicks: i64 = 0;
+    let mut clicks: Clicks = 0;
 
     for turn in turns {
-        let turn_result = dial.apply(turn);
+        let turn_result = dial.apply(*turn);
 
         dial = turn_result.dial;
         clicks += turn_result.clicks;
@@ -114,38 +212,527 @@ fn count_clicks(initial: Dial, turns: Vec<Turn>) -> i64 {
     clicks
 }
 
-pub fn part1() -> Result<i64, Box<dyn Error>> {
-    let turns = read_turns("inputs/day1.part1.txt")?;
+/// Like [count_clicks] but reading turns as it goes rather than needing them all in memory, for
+/// inputs too big to hold. Gives the final dial as well as the clicks.
+pub fn count_clicks_streaming<R: BufRead>(
+    initial: Dial,
+    mut reader: R,
+    source_name: &str,
+) -> Result<TurnResult> {
+    let mut total = TurnResult::new(initial, 0);
+    let mut line = String::new();
+
+    for i in 0.. {
+        line.clear();
+        let read = reader.read_line(&mut line).map_err(|error| Error::Io {
+            source_name: source_name.to_string(),
+            error,
+        })?;
+        if read == 0 {
+            break;
+        }
+
+        let line = line.trim_end_matches(['\n', '\r']);
+        if let Some(turn) = parse_line(line, Turn::parse).map_err(|e| e.shifted(i, 0))? {
+            let result = total.dial.apply(turn);
+            total = TurnResult::new(result.dial, total.clicks + result.clicks);
+        }
+    }
+
+    Ok(total)
+}
+
+/// [count_clicks] again but splitting the turns between `threads` threads. Each summarises its
+/// chunk for every possible starting direction, then the summaries get stitched together in order.
+/// There's never more than one thread per core whatever `threads` says, as extra ones only add
+/// summaries to stitch.
+pub fn count_clicks_parallel(initial: Dial, turns: &[Turn], threads: usize) -> Clicks {
+    if turns.is_empty() {
+        return 0;
+    }
+
+    let chunk_size = turns.len().div_ceil(usable_threads(threads));
+    let summaries: Vec<TurnsSummary> = thread::scope(|scope| {
+        let handles: Vec<_> = turns
+            .chunks(chunk_size)
+            .map(|chunk| scope.spawn(move || TurnsSummary::of(initial.size(), chunk)))
+            .collect();
+
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+
+    let mut dial = initial;
+    let mut clicks = 0;
+
+    for summary in &summaries {
+        let result = summary.apply(dial);
+
+        dial = result.dial;
+        clicks += result.clicks;
+    }
+
+    clicks
+}
+
+fn usable_threads(threads: usize) -> usize {
+    let cores = thread::available_parallelism().map_or(1, |n| n.get());
+
+    threads.clamp(1, cores)
+}
 
-    Ok(count_zeroes(Dial::new(), turns))
+/// What a run of turns does to a dial of a given size from any starting direction, without
+/// knowing the start up front.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TurnsSummary {
+    size: i64,
+    /// How far round the dial ends up from where it started
+    net: i64,
+    /// Clicks from complete turns, which happen wherever we start
+    full_turns: Clicks,
+    /// The rest of each turn clicks if it passes zero, which it does for a range of starting
+    /// directions. These are those ranges as sorted half-open starts and ends, with ranges that
+    /// wrap round split in two.
+    starts: Vec<i64>,
+    ends: Vec<i64>,
+    /// Starting directions which leave the dial on zero after one of the turns, sorted and
+    /// repeated for each turn they do so after
+    landings: Vec<i64>,
 }
 
-pub fn part2() -> Result<i64, Box<dyn Error>> {
-    let turns = read_turns("inputs/day1.part1.txt")?;
+impl TurnsSummary {
+    pub fn of(size: i64, turns: &[Turn]) -> TurnsSummary {
+        let mut summary = TurnsSummary {
+            size,
+            net: 0,
+            full_turns: 0,
+            starts: vec![],
+            ends: vec![],
+            landings: vec![],
+        };
+
+        for turn in turns {
+            let (full_turns, partial) = turn.split(size);
+            summary.full_turns += full_turns as Clicks;
+
+            if partial > 0 {
+                // Starting from s the turn passes zero if s + net + k is a multiple of the size for
+                // some k between 1 and the partial turn (negated going left)
+                let first = if turn.0 > 0 {
+                    -(summary.net + partial)
+                } else {
+                    1 - summary.net
+                };
+                summary.add_range(first.rem_euclid(size), partial);
+            }
+
+            summary.net = (summary.net + turn.0 % size).rem_euclid(size);
+            summary.landings.push((size - summary.net) % size);
+        }
+
+        summary.starts.sort_unstable();
+        summary.ends.sort_unstable();
+        summary.landings.sort_unstable();
+        summary
+    }
 
-    Ok(count_clicks(Dial::new(), turns))
+    fn add_range(&mut self, start: i64, length: i64) {
+        let end = start + length;
+
+        if end <= self.size {
+            self.starts.push(start);
+            self.ends.push(end);
+        } else {
+            self.starts.extend([start, 0]);
+            self.ends.extend([self.size, end - self.size]);
+        }
+    }
+
+    pub fn net(&self) -> i64 {
+        self.net
+    }
+
+    pub fn clicks_from(&self, direction: i64) -> Clicks {
+        let started = self.starts.partition_point(|s| *s <= direction);
+        let ended = self.ends.partition_point(|e| *e <= direction);
+
+        self.full_turns + (started - ended) as Clicks
+    }
+
+    pub fn zeroes_from(&self, direction: i64) -> Clicks {
+        let before = self.landings.partition_point(|l| *l < direction);
+        let upto = self.landings.partition_point(|l| *l <= direction);
+
+        (upto - before) as Clicks
+    }
+
+    /// The same as applying each of the turns to the dial in turn, which needs to be the size the
+    /// summary was made for
+    pub fn apply(&self, dial: Dial) -> TurnResult {
+        assert_eq!(
+            dial.size(),
+            self.size,
+            "summary is for a different size dial"
+        );
+
+        TurnResult::new(
+            Dial::sized(self.size, (dial.direction() + self.net) % self.size),
+            self.clicks_from(dial.direction()),
+        )
+    }
 }
 
-fn read_turns<P>(filename: P) -> io::Result<Vec<Turn>>
-where
-    P: AsRef<Path>,
-{
-    let file = File::open(filename)?;
+/// [count_zeroes] and [count_clicks] from every possible starting direction, indexed by direction
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StartCounts {
+    pub zeroes: Vec<Clicks>,
+    pub clicks: Vec<Clicks>,
+}
 
-    let mut result: Vec<Turn> = vec![];
+impl StartCounts {
+    /// There's an entry for every direction so huge dials are out
+    pub const MAX_SIZE: i64 = 1 << 24;
 
-    for line in io::BufReader::new(file).lines() {
-        result.push(Turn::parse(&line?));
+    pub fn of(size: i64, turns: &[Turn]) -> StartCounts {
+        let summary = TurnsSummary::of(size, turns);
+
+        StartCounts {
+            zeroes: (0..size).map(|d| summary.zeroes_from(d)).collect(),
+            clicks: (0..size).map(|d| summary.clicks_from(d)).collect(),
+        }
     }
 
-    Ok(result)
+    /// Zeroes for part 1 and clicks for part 2
+    pub fn for_part(&self, part: u32) -> Option<&[Clicks]> {
+        match part {
+            1 => Some(&self.zeroes),
+            2 => Some(&self.clicks),
+            _ => None,
+        }
+    }
+}
+
+/// How many starting directions give each count
+pub fn histogram(counts: &[Clicks]) -> BTreeMap<Clicks, usize> {
+    let mut histogram = BTreeMap::new();
+
+    for count in counts {
+        *histogram.entry(*count).or_default() += 1;
+    }
+
+    histogram
+}
+
+pub fn starts_giving(counts: &[Clicks], target: Clicks) -> Vec<i64> {
+    (0..)
+        .zip(counts)
+        .filter(|(_, count)| **count == target)
+        .map(|(direction, _)| direction)
+        .collect()
+}
+
+pub fn format_histogram(counts: &StartCounts, parts: &[u32]) -> String {
+    let rows: Vec<[String; 3]> = parts
+        .iter()
+        .filter_map(|part| Some((part, counts.for_part(*part)?)))
+        .flat_map(|(part, counts)| {
+            histogram(counts).into_iter().map(move |(count, starts)| {
+                [part.to_string(), count.to_string(), starts.to_string()]
+            })
+        })
+        .collect();
+
+    format_rows(["Part", "Count", "Starts"], &rows)
+}
+
+/// One turn of the dial, with where it started from
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TraceStep {
+    pub from: Dial,
+    pub turn: Turn,
+    pub result: TurnResult,
+}
+
+pub fn trace(initial: Dial, turns: &[Turn]) -> Vec<TraceStep> {
+    let mut dial = initial;
+
+    turns
+        .iter()
+        .map(|turn| {
+            let result = dial.apply(*turn);
+            let step = TraceStep {
+                from: dial,
+                turn: *turn,
+                result,
+            };
+
+            dial = result.dial;
+            step
+        })
+        .collect()
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum TraceFormat {
+    #[default]
+    Text,
+    Csv,
+}
+
+impl FromStr for TraceFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<TraceFormat> {
+        match s {
+            "text" => Ok(TraceFormat::Text),
+            "csv" => Ok(TraceFormat::Csv),
+            _ => Err(Error::Usage(format!(
+                "Unknown trace format {:?}, expected text or csv",
+                s
+            ))),
+        }
+    }
+}
+
+pub fn format_trace(steps: &[TraceStep], format: TraceFormat) -> String {
+    let rows: Vec<[String; 4]> = steps
+        .iter()
+        .map(|step| {
+            [
+                step.from.direction().to_string(),
+                step.turn.to_string(),
+                step.result.dial.direction().to_string(),
+                step.result.clicks.to_string(),
+            ]
+        })
+        .collect();
+    let header = ["From", "Turn", "To", "Clicks"];
+
+    match format {
+        TraceFormat::Text => format_rows(header, &rows),
+        TraceFormat::Csv => std::iter::once(header.map(str::to_lowercase))
+            .chain(rows)
+            .map(|row| row.join(","))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
+
+/// A turn for one of the dials in a [Lock]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Instruction {
+    /// Which dial, counting from 0 even though they're written counting from 1
+    pub dial: usize,
+    pub turn: Turn,
+}
+
+impl Instruction {
+    /// Like `2:L30` for the second dial, or just a turn on its own for the first
+    pub fn parse(s: &str) -> Result<Instruction, ParseError> {
+        let Some(colon) = s.find(':') else {
+            return Ok(Instruction {
+                dial: 0,
+                turn: Turn::parse(s)?,
+            });
+        };
+
+        let dial = s[..colon].trim();
+        let dial = dial
+            .parse::<usize>()
+            .ok()
+            .and_then(|d| d.checked_sub(1))
+            .ok_or_else(|| {
+                let leading = s.chars().take_while(|c| c.is_whitespace()).count();
+                ParseError::new(
+                    1,
+                    leading + 1,
+                    format!("expected a dial number from 1, found {:?}", dial),
+                )
+            })?;
+        let turn =
+            Turn::parse(&s[colon + 1..]).map_err(|e| e.shifted(0, s[..=colon].chars().count()))?;
+
+        Ok(Instruction { dial, turn })
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.dial + 1, self.turn)
+    }
+}
+
+/// Several dials turned independently, like the rings of a combination lock
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Lock {
+    dials: Vec<Dial>,
+}
+
+impl Lock {
+    pub fn new(dials: Vec<Dial>) -> Lock {
+        assert!(!dials.is_empty(), "a lock needs at least one dial");
+        Lock { dials }
+    }
+
+    pub fn dials(&self) -> &[Dial] {
+        &self.dials
+    }
+
+    pub fn all_zero(&self) -> bool {
+        self.dials.iter().all(|d| d.direction() == 0)
+    }
+
+    pub fn apply(&mut self, instruction: Instruction) -> Result<TurnResult> {
+        let dials = self.dials.len();
+        let dial = self.dials.get_mut(instruction.dial).ok_or_else(|| {
+            Error::InvalidPuzzle(format!(
+                "instruction {} is for dial {} but the lock only has {}",
+                instruction,
+                instruction.dial + 1,
+                dials
+            ))
+        })?;
+
+        let result = dial.apply(instruction.turn);
+        *dial = result.dial;
+
+        Ok(result)
+    }
+}
+
+/// How many times every dial reads zero after an instruction, the lock version of [count_zeroes]
+pub fn count_all_zeroes(initial: &Lock, instructions: &[Instruction]) -> Result<u64> {
+    let mut lock = initial.clone();
+    let mut count = 0;
+
+    for instruction in instructions {
+        lock.apply(*instruction)?;
+
+        if lock.all_zero() {
+            count += 1;
+        }
+    }
+
+    Ok(count)
+}
+
+/// The lock version of [count_clicks], how many times every dial reads zero at once including
+/// partway through turns. That's whenever the dial being turned clicks with the rest at zero.
+pub fn count_all_zero_clicks(initial: &Lock, instructions: &[Instruction]) -> Result<Clicks> {
+    let mut lock = initial.clone();
+    let mut clicks = 0;
+
+    for instruction in instructions {
+        let others_zero = lock
+            .dials()
+            .iter()
+            .enumerate()
+            .all(|(i, d)| i == instruction.dial || d.direction() == 0);
+        let result = lock.apply(*instruction)?;
+
+        if others_zero {
+            clicks += result.clicks;
+        }
+    }
+
+    Ok(clicks)
+}
+
+pub struct Day1;
+
+impl Puzzle for Day1 {
+    const DAY: u32 = 1;
+
+    type Input = Vec<Turn>;
+
+    fn parse(input: &str) -> Result<Vec<Turn>> {
+        Ok(parse_turns(input)?)
+    }
+
+    fn part1(turns: &Vec<Turn>) -> Result<Answer> {
+        Ok(Box::new(count_zeroes(Dial::new(), turns)))
+    }
+
+    fn part2(turns: &Vec<Turn>) -> Result<Answer> {
+        Ok(Box::new(count_clicks(Dial::new(), turns)))
+    }
+}
+
+/// [count_clicks] against [count_clicks_parallel], which has to stitch the threads' summaries back
+/// together in the right order
+pub struct ParallelClicks;
+
+impl CrossCheck for ParallelClicks {
+    const DAY: u32 = 1;
+    const NAME: &str = "parallel-clicks";
+
+    type Input = (Dial, Vec<Turn>);
+    type Output = Clicks;
+
+    fn real_inputs(input: &str) -> Result<Vec<(Dial, Vec<Turn>)>> {
+        Ok(vec![(Dial::new(), parse_turns(input)?)])
+    }
+
+    fn random_input(rng: &mut Rng) -> (Dial, Vec<Turn>) {
+        let size = rng.in_range(1..=200) as i64;
+        let dial = Dial::sized(size, rng.in_range(0..=size as u64 - 1) as i64);
+        let count = rng.in_range(0..=50) as usize;
+
+        (dial, synthetic::turns(rng, count, 500))
+    }
+
+    fn reference((dial, turns): &(Dial, Vec<Turn>)) -> Clicks {
+        count_clicks(*dial, turns)
+    }
+
+    fn optimised((dial, turns): &(Dial, Vec<Turn>)) -> Clicks {
+        count_clicks_parallel(*dial, turns, 4)
+    }
+}
+
+/// One turn per line, anything after a `#` is a comment and lines with nothing else are skipped
+pub fn parse_turns(input: &str) -> Result<Vec<Turn>, ParseError> {
+    parse_lines(input, Turn::parse)
+}
+
+/// [parse_turns] but for a [Lock], so each line can say which dial it's for
+pub fn parse_instructions(input: &str) -> Result<Vec<Instruction>, ParseError> {
+    parse_lines(input, Instruction::parse)
+}
+
+fn parse_lines<T>(
+    input: &str,
+    parse: fn(&str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            parse_line(line, parse)
+                .map_err(|e| e.shifted(i, 0))
+                .transpose()
+        })
+        .collect()
+}
+
+fn parse_line<T>(
+    line: &str,
+    parse: fn(&str) -> Result<T, ParseError>,
+) -> Result<Option<T>, ParseError> {
+    let line = line.split('#').next().unwrap_or_default();
+
+    if line.trim().is_empty() {
+        Ok(None)
+    } else {
+        parse(line).map(Some)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::day1::*;
+    use proptest::prelude::*;
 
-    static EXAMPLES: &'static str = "L68
+    static EXAMPLES: &str = "L68
                                      L30
                                      R48
                                      L5
@@ -170,9 +757,194 @@ mod tests {
         assert_eq!(Dial::of(50).apply(Turn(1000)), TurnResult::of(50, 10));
     }
 
+    #[test]
+    fn dial_other_sizes() {
+        let result = |size, direction, turn| {
+            let result = Dial::sized(size, direction).apply(Turn(turn));
+            (result.dial.direction(), result.clicks)
+        };
+
+        assert_eq!(result(10, 5, 5), (0, 1));
+        assert_eq!(result(10, 5, -6), (9, 1));
+        assert_eq!(result(10, 0, -1), (9, 0));
+        assert_eq!(result(10, 0, 25), (5, 2));
+        assert_eq!(result(60, 59, 1), (0, 1));
+        assert_eq!(result(60, 1, -121), (0, 3));
+        assert_eq!(result(1, 0, 7), (0, 7));
+        assert_eq!(result(1 << 32, 1, -2), ((1 << 32) - 1, 1));
+        assert_eq!(result(1 << 32, 1 << 31, i64::MAX), ((1 << 31) - 1, 1 << 31));
+        assert_eq!(
+            result(Dial::MAX_SIZE, Dial::MAX_SIZE - 1, Dial::MAX_SIZE - 1),
+            (Dial::MAX_SIZE - 2, 1)
+        );
+    }
+
+    #[test]
+    fn count_clicks_other_sizes() {
+        let turns = [Turn(-3), Turn(14), Turn(-11), Turn(30)];
+
+        assert_eq!(count_clicks(Dial::sized(10, 3), &turns), 1 + 1 + 1 + 3);
+        assert_eq!(count_zeroes(Dial::sized(10, 3), &turns), 1);
+        assert_eq!(count_clicks(Dial::sized(60, 3), &turns), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid direction: 10")]
+    fn dial_direction_must_fit() {
+        Dial::sized(10, 10);
+    }
+
+    #[test]
+    fn dial_extreme_turns() {
+        assert_eq!(
+            Dial::of(50).apply(Turn(i64::MIN)),
+            TurnResult::of(42, 92_233_720_368_547_758)
+        );
+        assert_eq!(
+            Dial::of(0).apply(Turn(i64::MAX)),
+            TurnResult::of(7, 92_233_720_368_547_758)
+        );
+        assert_eq!(
+            Dial::sized(2, 1).apply(Turn(i64::MIN)),
+            TurnResult::new(Dial::sized(2, 1), 1 << 62)
+        );
+        assert_eq!(
+            Dial::sized(Dial::MAX_SIZE, 1).apply(Turn(i64::MIN)),
+            TurnResult::new(Dial::sized(Dial::MAX_SIZE, Dial::MAX_SIZE - 1), 3)
+        );
+    }
+
+    #[test]
+    fn single_position_dial_biggest_turns() {
+        // One more click than an i64 can count
+        assert_eq!(
+            Dial::sized(1, 0).apply(Turn(i64::MIN)),
+            TurnResult::new(Dial::sized(1, 0), 1 << 63)
+        );
+        assert_eq!(
+            count_clicks(Dial::sized(1, 0), &[Turn(i64::MIN), Turn(i64::MAX)]),
+            u64::MAX as Clicks
+        );
+        // And two of them more than a u64 can
+        assert_eq!(
+            count_clicks(Dial::sized(1, 0), &[Turn(i64::MIN); 2]),
+            1 << 64
+        );
+        assert_eq!(
+            StartCounts::of(1, &[Turn(i64::MIN); 2]),
+            StartCounts {
+                zeroes: vec![2],
+                clicks: vec![1 << 64]
+            }
+        );
+    }
+
+    // One click at a time, far too slow for big turns but hard to get wrong
+    fn step_by_step(dial: Dial, turn: Turn) -> (i64, Clicks) {
+        let mut direction = dial.direction();
+        let mut clicks = 0;
+
+        for _ in 0..turn.0.unsigned_abs() {
+            direction = (direction + turn.0.signum()).rem_euclid(dial.size());
+            if direction == 0 {
+                clicks += 1;
+            }
+        }
+
+        (direction, clicks)
+    }
+
+    // Counts the multiples of the size passed over, in i128 so the biggest turns are fine
+    fn multiples_passed(dial: Dial, turn: Turn) -> (i128, i128) {
+        let (start, turn, size) = (
+            dial.direction() as i128,
+            turn.0 as i128,
+            dial.size() as i128,
+        );
+        let clicks = if turn > 0 {
+            (start + turn).div_euclid(size) - start.div_euclid(size)
+        } else {
+            (start - 1).div_euclid(size) - (start + turn - 1).div_euclid(size)
+        };
+
+        ((start + turn).rem_euclid(size), clicks)
+    }
+
+    fn small_dial() -> impl Strategy<Value = Dial> {
+        (1..=120i64).prop_flat_map(|size| (0..size).prop_map(move |d| Dial::sized(size, d)))
+    }
+
+    fn any_dial() -> impl Strategy<Value = Dial> {
+        prop_oneof![
+            small_dial(),
+            (1..=Dial::MAX_SIZE)
+                .prop_flat_map(|size| (0..size).prop_map(move |d| Dial::sized(size, d))),
+        ]
+    }
+
+    fn any_turn() -> impl Strategy<Value = Turn> {
+        prop_oneof![
+            (-1000..=1000i64).prop_map(Turn),
+            any::<i64>().prop_map(Turn),
+            prop_oneof![Just(i64::MIN), Just(i64::MIN + 1), Just(i64::MAX)].prop_map(Turn),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn apply_matches_step_by_step(dial in small_dial(), turn in -1000..=1000i64) {
+            let result = dial.apply(Turn(turn));
+
+            prop_assert_eq!(
+                (result.dial.direction(), result.clicks),
+                step_by_step(dial, Turn(turn))
+            );
+        }
+
+        #[test]
+        fn apply_matches_multiples_passed(dial in any_dial(), turn in any_turn()) {
+            let result = dial.apply(turn);
+
+            prop_assert_eq!(
+                (result.dial.direction() as i128, result.clicks as i128),
+                multiples_passed(dial, turn)
+            );
+        }
+
+        #[test]
+        fn count_clicks_matches_step_by_step(
+            dial in small_dial(),
+            turns in prop::collection::vec((-500..=500i64).prop_map(Turn), 0..50)
+        ) {
+            let (_, clicks) = turns.iter().fold((dial, 0), |(dial, clicks), turn| {
+                let (direction, more) = step_by_step(dial, *turn);
+                (Dial::sized(dial.size(), direction), clicks + more)
+            });
+
+            prop_assert_eq!(count_clicks(dial, &turns), clicks);
+        }
+
+        #[test]
+        fn summary_matches_applying_turns_one_by_one(
+            dial in any_dial(),
+            turns in prop::collection::vec(any_turn(), 0..20)
+        ) {
+            let summary = TurnsSummary::of(dial.size(), &turns);
+            let expected = turns.iter().fold(TurnResult::new(dial, 0), |total, turn| {
+                let result = total.dial.apply(*turn);
+                TurnResult::new(result.dial, total.clicks + result.clicks)
+            });
+
+            prop_assert_eq!(summary.apply(dial), expected);
+        }
+    }
+
     #[test]
     fn turn_parse_examples() {
-        let turns: Vec<Turn> = EXAMPLES.lines().map(|l| Turn::parse(l.trim())).collect();
+        let turns: Vec<Turn> = EXAMPLES
+            .lines()
+            .map(|l| Turn::parse(l.trim()).unwrap())
+            .collect();
 
         assert_eq!(
             turns,
@@ -194,7 +966,10 @@ mod tests {
     #[test]
     fn dial_turn_using_examples() {
         let mut dial = Dial::new();
-        let turns: Vec<Turn> = EXAMPLES.lines().map(|l| Turn::parse(l.trim())).collect();
+        let turns: Vec<Turn> = EXAMPLES
+            .lines()
+            .map(|l| Turn::parse(l.trim()).unwrap())
+            .collect();
 
         for turn in turns {
             dial = dial.apply(turn).dial;
@@ -206,16 +981,297 @@ mod tests {
     #[test]
     fn count_zeroes_using_examples() {
         let dial = Dial::new();
-        let turns: Vec<Turn> = EXAMPLES.lines().map(|l| Turn::parse(l.trim())).collect();
+        let turns: Vec<Turn> = EXAMPLES
+            .lines()
+            .map(|l| Turn::parse(l.trim()).unwrap())
+            .collect();
 
-        assert_eq!(count_zeroes(dial, turns), 3);
+        assert_eq!(count_zeroes(dial, &turns), 3);
     }
 
     #[test]
     fn count_clicks_using_examples() {
         let dial = Dial::new();
-        let turns: Vec<Turn> = EXAMPLES.lines().map(|l| Turn::parse(l.trim())).collect();
+        let turns: Vec<Turn> = EXAMPLES
+            .lines()
+            .map(|l| Turn::parse(l.trim()).unwrap())
+            .collect();
+
+        assert_eq!(count_clicks(dial, &turns), 6);
+    }
+
+    #[test]
+    fn count_clicks_streaming_examples() {
+        let input = EXAMPLES.replace(' ', "") + "\n# and a comment\n";
+
+        assert_eq!(
+            count_clicks_streaming(Dial::new(), input.as_bytes(), "examples").unwrap(),
+            TurnResult::of(32, 6)
+        );
+        assert_eq!(
+            count_clicks_streaming(Dial::new(), "L1\nR2\nR3x\n".as_bytes(), "x")
+                .unwrap_err()
+                .to_string(),
+            "line 3, column 3: expected a turn like L68 or -68, found \"R3x\""
+        );
+    }
+
+    #[test]
+    fn summary_matches_applying_turns() {
+        let turns = [Turn(-3), Turn(14), Turn(-11), Turn(30), Turn(-10), Turn(7)];
 
-        assert_eq!(count_clicks(dial, turns), 6);
+        for size in [1, 7, 10, 60] {
+            let summary = TurnsSummary::of(size, &turns);
+
+            for direction in 0..size {
+                let dial = Dial::sized(size, direction);
+                let expected = TurnResult::new(
+                    turns.iter().fold(dial, |d, t| d.apply(*t).dial),
+                    count_clicks(dial, &turns),
+                );
+
+                assert_eq!(
+                    summary.apply(dial),
+                    expected,
+                    "size {size} from {direction}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn count_clicks_parallel_matches_sequential() {
+        let turns = crate::synthetic::turns(&mut crate::synthetic::Rng::new(1), 1000, 250);
+
+        for threads in [0, 1, 3, 8, 2000] {
+            assert_eq!(
+                count_clicks_parallel(Dial::new(), &turns, threads),
+                count_clicks(Dial::new(), &turns)
+            );
+            assert_eq!(
+                count_clicks_parallel(Dial::sized(7, 3), &turns, threads),
+                count_clicks(Dial::sized(7, 3), &turns)
+            );
+        }
+        assert_eq!(count_clicks_parallel(Dial::new(), &[], 4), 0);
+
+        assert_eq!(usable_threads(0), 1);
+        assert!(usable_threads(2000) <= thread::available_parallelism().unwrap().get());
+    }
+
+    #[test]
+    fn start_counts_match_counting_from_each_start() {
+        let turns = parse_turns(&EXAMPLES.replace(' ', "")).unwrap();
+        let counts = StartCounts::of(100, &turns);
+
+        for direction in 0..100 {
+            let dial = Dial::sized(100, direction);
+
+            assert_eq!(
+                counts.zeroes[direction as usize],
+                count_zeroes(dial, &turns) as Clicks
+            );
+            assert_eq!(
+                counts.clicks[direction as usize],
+                count_clicks(dial, &turns)
+            );
+        }
+        assert_eq!(counts.zeroes[50], 3);
+        assert_eq!(counts.clicks[50], 6);
+    }
+
+    #[test]
+    fn histogram_and_matching_starts() {
+        let counts = StartCounts::of(4, &[Turn(1), Turn(2), Turn(-7)]);
+
+        assert_eq!(counts.zeroes, vec![1, 1, 0, 1]);
+        assert_eq!(counts.clicks, vec![2, 2, 3, 3]);
+        assert_eq!(histogram(&counts.clicks), BTreeMap::from([(2, 2), (3, 2)]));
+        assert_eq!(starts_giving(&counts.zeroes, 1), vec![0, 1, 3]);
+        assert_eq!(starts_giving(&counts.zeroes, 2), vec![]);
+        assert_eq!(
+            format_histogram(&counts, &[1, 2]),
+            "Part | Count | Starts\n\
+             -----+-------+-------\n\
+             1    | 0     | 1\n\
+             1    | 1     | 3\n\
+             2    | 2     | 2\n\
+             2    | 3     | 2"
+        );
+    }
+
+    #[test]
+    fn instruction_parse() {
+        let parse = |s| Instruction::parse(s).unwrap();
+
+        assert_eq!(
+            parse("2:L30"),
+            Instruction {
+                dial: 1,
+                turn: Turn(-30)
+            }
+        );
+        assert_eq!(parse(" 3 : +5").dial, 2);
+        assert_eq!(
+            parse("R7"),
+            Instruction {
+                dial: 0,
+                turn: Turn(7)
+            }
+        );
+        assert_eq!(parse("12:r1").to_string(), "12:R1");
+    }
+
+    #[test]
+    fn instruction_parse_errors() {
+        assert_eq!(
+            Instruction::parse("0:L5"),
+            Err(ParseError::new(
+                1,
+                1,
+                "expected a dial number from 1, found \"0\""
+            ))
+        );
+        assert_eq!(Instruction::parse("  x:L5").unwrap_err().column, 3);
+        // An ideographic space is one character but three bytes
+        assert_eq!(Instruction::parse("\u{3000}x:L5").unwrap_err().column, 2);
+        assert_eq!(Instruction::parse("2:L5x").unwrap_err().column, 5);
+        assert_eq!(
+            parse_instructions("1:L5\n# comment\n2:X").unwrap_err(),
+            ParseError::new(3, 3, "expected a turn like L68 or -68, found \"X\"")
+        );
+    }
+
+    #[test]
+    fn lock_counts() {
+        let lock = Lock::new(vec![Dial::sized(10, 5), Dial::sized(4, 1)]);
+        let instructions = parse_instructions("1:R5\n2:L1\n1:L20\n2:R8\n1:R3").unwrap();
+
+        // Both zero after the second, third and fourth, which each turn one dial through zero
+        // with the other already there, once and then twice and twice
+        assert_eq!(count_all_zeroes(&lock, &instructions).unwrap(), 3);
+        assert_eq!(
+            count_all_zero_clicks(&lock, &instructions).unwrap(),
+            1 + 2 + 2
+        );
+        assert!(matches!(
+            count_all_zeroes(&lock, &parse_instructions("3:L1").unwrap()),
+            Err(Error::InvalidPuzzle(_))
+        ));
+    }
+
+    #[test]
+    fn single_dial_lock_matches_dial() {
+        let turns = parse_turns(&EXAMPLES.replace(' ', "")).unwrap();
+        let instructions: Vec<Instruction> = turns
+            .iter()
+            .map(|turn| Instruction {
+                dial: 0,
+                turn: *turn,
+            })
+            .collect();
+        let lock = Lock::new(vec![Dial::new()]);
+
+        assert_eq!(count_all_zeroes(&lock, &instructions).unwrap(), 3);
+        assert_eq!(count_all_zero_clicks(&lock, &instructions).unwrap(), 6);
+    }
+
+    #[test]
+    fn trace_examples() {
+        let turns = parse_turns("L68\nL30\nR48").unwrap();
+        let steps = trace(Dial::new(), &turns);
+
+        assert_eq!(
+            steps[1],
+            TraceStep {
+                from: Dial::of(82),
+                turn: Turn(-30),
+                result: TurnResult::of(52, 0),
+            }
+        );
+        assert_eq!(
+            format_trace(&steps, TraceFormat::Csv),
+            "from,turn,to,clicks\n50,L68,82,1\n82,L30,52,0\n52,R48,0,1"
+        );
+        assert_eq!(
+            format_trace(&steps, TraceFormat::Text),
+            "From | Turn | To | Clicks\n\
+             -----+------+----+-------\n\
+             50   | L68  | 82 | 1\n\
+             82   | L30  | 52 | 0\n\
+             52   | R48  | 0  | 1"
+        );
+    }
+
+    #[test]
+    fn turn_parse_errors() {
+        assert_eq!(
+            Turn::parse("X12"),
+            Err(ParseError::new(
+                1,
+                1,
+                "expected a turn like L68 or -68, found \"X12\""
+            ))
+        );
+        assert_eq!(
+            Turn::parse("L1x2"),
+            Err(ParseError::new(
+                1,
+                3,
+                "expected a turn like L68 or -68, found \"L1x2\""
+            ))
+        );
+        assert_eq!(Turn::parse("R").unwrap_err().column, 2);
+        assert_eq!(Turn::parse("").unwrap_err().column, 1);
+        assert_eq!(Turn::parse("L99999999999999999999").unwrap_err().column, 2);
+        assert_eq!(
+            Turn::parse("L9223372036854775809"),
+            Err(ParseError::new(
+                1,
+                2,
+                "invalid turn amount: number too large to fit in target type"
+            ))
+        );
+        assert_eq!(Turn::parse("9223372036854775808").unwrap_err().column, 1);
+        assert_eq!(Turn::parse("  L 1 2").unwrap_err().column, 7);
+        assert_eq!(Turn::parse("-L5").unwrap_err().column, 2);
+        assert_eq!(Turn::parse("+").unwrap_err().column, 2);
+    }
+
+    #[test]
+    fn turn_parse_syntaxes() {
+        let parse = |s| Turn::parse(s).unwrap();
+
+        assert_eq!(parse("L68"), Turn(-68));
+        assert_eq!(parse("l68"), Turn(-68));
+        assert_eq!(parse("r48"), Turn(48));
+        assert_eq!(parse("L 5"), Turn(-5));
+        assert_eq!(parse("  R5\t"), Turn(5));
+        assert_eq!(parse("-68"), Turn(-68));
+        assert_eq!(parse("+48"), Turn(48));
+        assert_eq!(parse("48"), Turn(48));
+        assert_eq!(parse("R0"), Turn(0));
+
+        // The biggest turns each way round-trip
+        for turn in [Turn(i64::MIN), Turn(i64::MAX)] {
+            assert_eq!(Turn::parse(&turn.to_string()), Ok(turn));
+        }
+        assert_eq!(parse("-9223372036854775808"), Turn(i64::MIN));
+    }
+
+    #[test]
+    fn parse_turns_skips_comments_and_blank_lines() {
+        assert_eq!(
+            parse_turns("# A comment\nL68\n\n  \nR48 # another\n-5\n").unwrap(),
+            vec![Turn(-68), Turn(48), Turn(-5)]
+        );
+    }
+
+    #[test]
+    fn parse_turns_reports_line() {
+        assert_eq!(
+            parse_turns("L1\nR2\nR3x\n").unwrap_err(),
+            ParseError::new(3, 3, "expected a turn like L68 or -68, found \"R3x\"")
+        );
     }
 }
@@ -1,146 +1,707 @@
+use crate::cross_check::CrossCheck;
+use crate::day5::{overlap, simplify_ranges};
+use crate::error::{Error, ParseError, Result};
+use crate::runner::format_rows;
+use crate::solution::{Answer, Puzzle};
+use crate::synthetic::{self, Rng};
 use itertools::Itertools;
-use std::cmp::max;
-use std::error::Error;
-use std::fs::File;
-use std::io::Read;
+use std::cmp::{max, min};
+use std::collections::BTreeSet;
+use std::fmt;
 use std::num::ParseIntError;
 use std::ops::RangeInclusive;
-use std::str::FromStr;
 
-type IdRange = RangeInclusive<u64>;
+/// Wide enough for IDs up to 39 digits, beyond that they don't parse
+pub type Id = u128;
 
-pub fn part1() -> Result<u64, Box<dyn Error>> {
-    let mut input = String::new();
-    File::open("inputs/day2.part1.txt")?.read_to_string(&mut input)?;
-    let ranges = parse_ranges(input.as_str())?;
+pub type IdRange = RangeInclusive<Id>;
 
-    Ok(ranges
-        .iter()
-        .map(|r| find_all_invalid_ids_with_exact_repetition(r, 2).sum::<u64>())
-        .sum())
+pub struct Day2;
+
+impl Puzzle for Day2 {
+    const DAY: u32 = 2;
+
+    type Input = Vec<IdRange>;
+
+    fn parse(input: &str) -> Result<Vec<IdRange>> {
+        Ok(parse_ranges(input, Radix::DECIMAL)?)
+    }
+
+    fn part1(ranges: &Vec<IdRange>) -> Result<Answer> {
+        Ok(Box::new(sum_all_invalid_ids(
+            ranges,
+            &RepetitionPolicy::Exactly(2),
+            Radix::DECIMAL,
+        )?))
+    }
+
+    fn part2(ranges: &Vec<IdRange>) -> Result<Answer> {
+        Ok(Box::new(sum_all_invalid_ids(
+            ranges,
+            &RepetitionPolicy::AtLeast(2),
+            Radix::DECIMAL,
+        )?))
+    }
 }
 
-pub fn part2() -> Result<u64, Box<dyn Error>> {
-    let mut input = String::new();
-    File::open("inputs/day2.part1.txt")?.read_to_string(&mut input)?;
-    let ranges = parse_ranges(input.as_str())?;
+/// [find_invalid_ids] against [find_invalid_ids_brute_force], over every policy part 1 or 2 uses
+/// on the real ranges and all of them in a few radixes on made-up ones
+pub struct GeneratedIds;
 
-    // The brute force version runs so fast I can leave this amazing parity check in! I don't regret
-    // wasting my time at all, in case you were wondering!
-    ranges.iter().for_each(|range| {
-        assert_eq!(
-            find_all_invalid_ids(range).sorted().collect::<Vec<_>>(),
-            find_all_invalid_ids_brute_force(range).collect::<Vec<_>>(),
-            "Range: {:?}",
-            range
-        )
-    });
+impl CrossCheck for GeneratedIds {
+    const DAY: u32 = 2;
+    const NAME: &str = "generated-ids";
+
+    type Input = (IdRange, RepetitionPolicy, Radix);
+    type Output = Vec<Id>;
+
+    fn real_inputs(input: &str) -> Result<Vec<(IdRange, RepetitionPolicy, Radix)>> {
+        let policies = [RepetitionPolicy::Exactly(2), RepetitionPolicy::AtLeast(2)];
+
+        Ok(parse_ranges(input, Radix::DECIMAL)?
+            .into_iter()
+            .cartesian_product(policies)
+            .map(|(range, policy)| (range, policy, Radix::DECIMAL))
+            .collect())
+    }
+
+    fn random_input(rng: &mut Rng) -> (IdRange, RepetitionPolicy, Radix) {
+        let digits = rng.in_range(1..=9) as u32;
+        let width = rng.in_range(1..=5000);
+        let range = synthetic::id_range(rng, digits, width);
+        let times = rng.in_range(1..=4) as u32;
+        let policy = match rng.in_range(0..=3) {
+            0 => RepetitionPolicy::Exactly(times),
+            1 => RepetitionPolicy::AtLeast(times),
+            2 => RepetitionPolicy::UnitLengths((1..=times).collect()),
+            _ => RepetitionPolicy::Palindromic,
+        };
+        let radices = [2, 3, 10, 16, 36];
+        let radix = Radix(radices[rng.in_range(0..=radices.len() as u64 - 1) as usize]);
+
+        (range, policy, radix)
+    }
+
+    fn reference((range, policy, radix): &(IdRange, RepetitionPolicy, Radix)) -> Vec<Id> {
+        find_invalid_ids_brute_force(range, policy, *radix).collect()
+    }
+
+    fn optimised((range, policy, radix): &(IdRange, RepetitionPolicy, Radix)) -> Vec<Id> {
+        find_invalid_ids(range, policy, *radix).collect()
+    }
+}
+
+/// The base IDs are written in, which changes which ones are repeats: 0x1212 is one but 4626 isn't
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Radix(u32);
+
+impl Radix {
+    pub const DECIMAL: Radix = Radix(10);
+    pub const HEX: Radix = Radix(16);
+    pub const BASE36: Radix = Radix(36);
+
+    /// 2 to 36, as far as digits and letters go
+    pub fn new(radix: u32) -> Option<Radix> {
+        (2..=36).contains(&radix).then_some(Radix(radix))
+    }
+
+    pub fn get(self) -> u32 {
+        self.0
+    }
+
+    fn pow(self, exponent: u32) -> Id {
+        Id::pow(self.0 as Id, exponent)
+    }
+
+    fn checked_pow(self, exponent: u32) -> Option<Id> {
+        Id::checked_pow(self.0 as Id, exponent)
+    }
+
+    /// How many digits the ID has written in this radix, which had better be positive
+    pub fn digits(self, id: Id) -> u32 {
+        id.ilog(self.0 as Id) + 1
+    }
+
+    fn largest_with_digits(self, digits: u32) -> Id {
+        self.checked_pow(digits).map_or(Id::MAX, |p| p - 1)
+    }
+
+    pub fn parse(self, str: &str) -> Result<Id, ParseIntError> {
+        Id::from_str_radix(str, self.0)
+    }
+
+    /// Letters come out lower case, parsing takes either
+    pub fn format(self, id: Id) -> String {
+        let mut digits = vec![];
+        let mut rest = id;
+
+        loop {
+            digits.push(char::from_digit((rest % self.0 as Id) as u32, self.0).unwrap());
+            rest /= self.0 as Id;
+            if rest == 0 {
+                break;
+            }
+        }
+
+        digits.iter().rev().collect()
+    }
+}
+
+/// Which IDs count as invalid
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RepetitionPolicy {
+    /// Some unit repeated exactly this many times, part 1 is `Exactly(2)`
+    Exactly(u32),
+    /// Some unit repeated this many times or more, part 2 is `AtLeast(2)`
+    AtLeast(u32),
+    /// A unit of one of these lengths repeated at least twice
+    UnitLengths(BTreeSet<u32>),
+    /// Reads the same backwards, with at least two digits as otherwise every small ID would be
+    Palindromic,
+}
+
+impl RepetitionPolicy {
+    /// Lengths of the prefix that an invalid ID with this many digits is built from, which is the
+    /// repeated unit or the front half of a palindrome
+    fn prefix_lengths(&self, digits: u32) -> Vec<u32> {
+        let repeats =
+            |accept: &dyn Fn(u32) -> bool| divisors(digits).filter(|unit| accept(*unit)).collect();
+
+        match self {
+            RepetitionPolicy::Exactly(k) => repeats(&|unit| digits / unit == *k),
+            RepetitionPolicy::AtLeast(k) => repeats(&|unit| digits / unit >= *k),
+            RepetitionPolicy::UnitLengths(lengths) => {
+                repeats(&|unit| lengths.contains(&unit) && digits / unit >= 2)
+            }
+            RepetitionPolicy::Palindromic if digits >= 2 => vec![digits.div_ceil(2)],
+            RepetitionPolicy::Palindromic => vec![],
+        }
+    }
+
+    /// The invalid ID with this many digits built from a prefix of one of the [prefix_lengths],
+    /// or nothing if it's too big. Bigger prefixes always build bigger IDs.
+    ///
+    /// [prefix_lengths]: RepetitionPolicy::prefix_lengths
+    fn build(&self, prefix: Id, digits: u32, radix: Radix) -> Option<Id> {
+        match self {
+            RepetitionPolicy::Palindromic => palindrome(prefix, digits, radix),
+            _ => invalid_id(prefix, digits / radix.digits(prefix), radix),
+        }
+    }
+
+    /// The brute force check, whether this one ID is invalid
+    pub fn accepts(&self, id: Id, radix: Radix) -> bool {
+        let digits = radix.digits(id);
+
+        self.prefix_lengths(digits)
+            .into_iter()
+            .any(|pl| self.build(id / radix.pow(digits - pl), digits, radix) == Some(id))
+    }
+}
+
+fn divisors(n: u32) -> impl Iterator<Item = u32> {
+    (1..=n).filter(move |d| n.is_multiple_of(*d))
+}
+
+/// Adding up enough big IDs can go past even a u128
+pub fn sum_ids(ids: impl IntoIterator<Item = Id>) -> Result<Id> {
+    ids.into_iter()
+        .try_fold(0, Id::checked_add)
+        .ok_or_else(too_big)
+}
 
-    Ok(ranges
+fn too_big() -> Error {
+    Error::InvalidPuzzle("sum of invalid IDs is too big".to_string())
+}
+
+pub fn sum_all_invalid_ids(
+    ranges: &[IdRange],
+    policy: &RepetitionPolicy,
+    radix: Radix,
+) -> Result<Id> {
+    let sums: Vec<Id> = ranges
         .iter()
-        .map(|r| find_all_invalid_ids(r).sum::<u64>())
-        .sum())
+        .map(|r| sum_invalid_ids(r, policy, radix))
+        .try_collect()?;
+
+    sum_ids(sums)
+}
+
+/// Which IDs are invalid for each part of the puzzle
+pub fn part_policy(part: u32) -> Option<RepetitionPolicy> {
+    match part {
+        1 => Some(RepetitionPolicy::Exactly(2)),
+        2 => Some(RepetitionPolicy::AtLeast(2)),
+        _ => None,
+    }
+}
+
+/// How many invalid IDs there are for each part and what they add up to, written in the radix
+pub fn format_totals(ranges: &[IdRange], parts: &[u32], radix: Radix) -> Result<String> {
+    let rows: Vec<[String; 3]> = parts
+        .iter()
+        .filter_map(|part| Some((part, part_policy(*part)?)))
+        .map(|(part, policy)| {
+            let count = sum_ids(ranges.iter().map(|r| count_invalid_ids(r, &policy, radix)))?;
+            let sum = sum_all_invalid_ids(ranges, &policy, radix)?;
+
+            Ok([part.to_string(), count.to_string(), radix.format(sum)])
+        })
+        .collect::<Result<_>>()?;
+
+    Ok(format_rows(["Part", "Invalid IDs", "Sum"], &rows))
+}
+
+/// The same IDs as the ranges cover but with overlapping ranges merged, so summing over these counts
+/// an ID in more than one range once rather than once per range
+pub fn normalise_ranges(ranges: &[IdRange]) -> Vec<IdRange> {
+    simplify_ranges(ranges)
+}
+
+/// Positions of each pair of ranges in the list which have IDs in common
+pub fn overlapping_ranges(ranges: &[IdRange]) -> Vec<(usize, usize)> {
+    (0..ranges.len())
+        .tuple_combinations()
+        .filter(|(l, r)| overlap(&ranges[*l], &ranges[*r]))
+        .collect()
 }
 
-fn find_all_invalid_ids_brute_force(range: &IdRange) -> impl Iterator<Item = u64> {
-    range.clone().filter(|i| contains_repetition(*i))
+pub fn format_overlaps(ranges: &[IdRange], radix: Radix) -> String {
+    let range = |i: usize| {
+        format!(
+            "{}-{} (range {})",
+            radix.format(*ranges[i].start()),
+            radix.format(*ranges[i].end()),
+            i + 1
+        )
+    };
+
+    overlapping_ranges(ranges)
+        .into_iter()
+        .map(|(l, r)| format!("{} overlaps {}", range(l), range(r)))
+        .join("\n")
 }
 
-fn contains_repetition(i: u64) -> bool {
-    let digits = i.ilog10() + 1;
+/// Adds up the invalid IDs in a range without finding them one by one, so it doesn't matter how
+/// many there are. Except for palindromes, which don't add up so neatly and do get found one by
+/// one, though there are only about as many as the square root of the range's width.
+pub fn sum_invalid_ids(range: &IdRange, policy: &RepetitionPolicy, radix: Radix) -> Result<Id> {
+    if *policy == RepetitionPolicy::Palindromic {
+        return sum_ids(find_invalid_ids(range, policy, radix));
+    }
 
-    (1..=(digits / 2)).any(|pl| {
-        let prefix = i / 10u64.pow(digits - pl);
+    measure_invalid_ids(range, policy, radix, |first, last, repunit| {
+        // Halve whichever of these is even before multiplying, to put off overflowing
+        let count = last - first + 1;
+        let ends = first.checked_add(last)?;
+        let (a, b) = if count.is_multiple_of(2) {
+            (count / 2, ends)
+        } else {
+            (count, ends / 2)
+        };
 
-        i == invalid_id(prefix, digits / pl)
+        a.checked_mul(b)?.checked_mul(repunit)
     })
+    .ok_or_else(too_big)
 }
 
-fn find_all_invalid_ids(range: &IdRange) -> impl Iterator<Item = u64> {
-    let max_prefix_length = (range.end().ilog10() / 2) + 1;
+pub fn count_invalid_ids(range: &IdRange, policy: &RepetitionPolicy, radix: Radix) -> Id {
+    if *policy == RepetitionPolicy::Palindromic {
+        // Only one prefix length so nothing gets counted twice
+        return digit_lengths(range, radix)
+            .flat_map(|digits| prefixes(range, policy, digits, radix))
+            .map(|prefixes| {
+                if prefixes.is_empty() {
+                    0
+                } else {
+                    prefixes.end() - prefixes.start() + 1
+                }
+            })
+            .sum();
+    }
 
-    (1..=max_prefix_length)
-        .flat_map(|pl| invalid_ids_with_prefix_length(range, pl))
-        .unique()
+    measure_invalid_ids(range, policy, radix, |first, last, _| {
+        Some(last - first + 1)
+    })
+    .expect("can't be more invalid IDs than IDs")
 }
 
-fn find_all_invalid_ids_with_exact_repetition(
+// Every invalid ID with d digits is some unit of length u repeated d / u times, which is the unit
+// times a "repunit" like 1001001 (in whatever radix). So the ones in a range are an arithmetic
+// series of units for each length, easy to count or sum. Different lengths overlap though (222222
+// is 2, 22 and 222 repeated) so this goes through the lengths shortest first, taking off what
+// shorter lengths already covered to get the IDs whose shortest unit is exactly that long, and adds
+// those up. `measure` gets the first and last unit of a series and the repunit, and gives nothing
+// if the answer would overflow.
+fn measure_invalid_ids(
     range: &IdRange,
-    repetitions: u32,
-) -> impl Iterator<Item = u64> {
-    // Having to do the max here makes me worried that I screwed this logic up
-    let first_length = max(1, (range.start().ilog10() + 1) / repetitions);
+    policy: &RepetitionPolicy,
+    radix: Radix,
+    measure: impl Fn(Id, Id, Id) -> Option<Id>,
+) -> Option<Id> {
+    let mut total: Id = 0;
+
+    for digits in digit_lengths(range, radix) {
+        let block = digit_block(range, digits, radix);
+        let unit_lengths = policy.prefix_lengths(digits);
+
+        // Only lengths which go into an acceptable one matter, which saves measuring the whole
+        // block as one big unit and overflowing for no reason
+        let mut shortest_units: Vec<(u32, Id)> = vec![];
+        for unit in divisors(digits).filter(|d| unit_lengths.iter().any(|u| u.is_multiple_of(*d))) {
+            // Near the top of an Id some radixes have a repunit too big for one, and then even the
+            // smallest unit repeated is too, so there's nothing of this length to measure
+            let repunit = (0..digits / unit)
+                .try_fold(0, |repunit: Id, i| repunit.checked_add(radix.pow(i * unit)));
+            let all = match repunit {
+                Some(repunit) => {
+                    let first = max(block.start().div_ceil(repunit), radix.pow(unit - 1));
+                    let last = min(block.end() / repunit, radix.largest_with_digits(unit));
+
+                    if first <= last {
+                        measure(first, last, repunit)?
+                    } else {
+                        0
+                    }
+                }
+                None => 0,
+            };
+            let shorter: Id = shortest_units
+                .iter()
+                .filter(|(shorter, _)| unit.is_multiple_of(*shorter))
+                .map(|(_, measured)| measured)
+                .sum();
+            let shortest = all - shorter;
+
+            total = total.checked_add(shortest)?;
+            shortest_units.push((unit, shortest));
+        }
+    }
+
+    Some(total)
+}
+
+fn digit_lengths(range: &IdRange, radix: Radix) -> RangeInclusive<u32> {
+    radix.digits(*range.start())..=radix.digits(*range.end())
+}
+
+/// The part of the range with this many digits
+fn digit_block(range: &IdRange, digits: u32, radix: Radix) -> IdRange {
+    max(*range.start(), radix.pow(digits - 1))
+        ..=min(*range.end(), radix.largest_with_digits(digits))
+}
+
+/// Prefixes which build invalid IDs with this many digits inside the range, one run of them for
+/// each prefix length
+fn prefixes(
+    range: &IdRange,
+    policy: &RepetitionPolicy,
+    digits: u32,
+    radix: Radix,
+) -> impl Iterator<Item = IdRange> {
+    let block = digit_block(range, digits, radix);
+
+    policy
+        .prefix_lengths(digits)
+        .into_iter()
+        .map(move |pl| {
+            let front = |id: Id| id / radix.pow(digits - pl);
+
+            // The front of each end of the block is the right prefix or one off
+            let mut first = front(*block.start());
+            if policy
+                .build(first, digits, radix)
+                .is_none_or(|id| id < *block.start())
+            {
+                first += 1;
+            }
+            let mut last = front(*block.end());
+            if policy
+                .build(last, digits, radix)
+                .is_none_or(|id| id > *block.end())
+            {
+                last -= 1;
+            }
+
+            first..=last
+        })
+        .collect::<Vec<_>>()
+        .into_iter()
+}
+
+/// Every invalid ID in the range, in order, generated directly from their prefixes
+pub fn find_invalid_ids(
+    range: &IdRange,
+    policy: &RepetitionPolicy,
+    radix: Radix,
+) -> impl Iterator<Item = Id> + use<> {
+    digit_lengths(range, radix)
+        .flat_map(|digits| {
+            prefixes(range, policy, digits, radix).map(move |prefixes| (digits, prefixes))
+        })
+        .map(|(digits, prefixes)| {
+            let policy = policy.clone();
+            prefixes.filter_map(move |prefix| policy.build(prefix, digits, radix))
+        })
+        .collect::<Vec<_>>()
+        .into_iter()
+        // Each run is in order but they overlap where an ID has more than one unit
+        .kmerge()
+        .dedup()
+}
+
+/// [find_invalid_ids] the slow way, checking every ID in the range
+pub fn find_invalid_ids_brute_force(
+    range: &IdRange,
+    policy: &RepetitionPolicy,
+    radix: Radix,
+) -> impl Iterator<Item = Id> + use<> {
+    let policy = policy.clone();
+
+    range.clone().filter(move |id| policy.accepts(*id, radix))
+}
+
+// These are all the original decimal puzzle
+
+pub fn find_all_invalid_ids_brute_force(range: &IdRange) -> impl Iterator<Item = Id> + use<> {
+    find_invalid_ids_brute_force(range, &RepetitionPolicy::AtLeast(2), Radix::DECIMAL)
+}
+
+pub fn contains_repetition(i: Id) -> bool {
+    RepetitionPolicy::AtLeast(2).accepts(i, Radix::DECIMAL)
+}
 
-    (first_length..)
-        .flat_map(move |pl| invalid_ids_with_prefix_and_repetitions(pl, repetitions))
-        .take_while(|id| range.end() >= id)
-        .filter(|id| range.contains(id))
+pub fn find_all_invalid_ids(range: &IdRange) -> impl Iterator<Item = Id> + use<> {
+    find_invalid_ids(range, &RepetitionPolicy::AtLeast(2), Radix::DECIMAL)
 }
 
-fn invalid_ids_with_prefix_and_repetitions(
-    prefix_length: u32,
+pub fn find_all_invalid_ids_with_exact_repetition(
+    range: &IdRange,
     repetitions: u32,
-) -> impl Iterator<Item = u64> {
-    let first_prefix = 10u64.pow(prefix_length - 1);
+) -> impl Iterator<Item = Id> + use<> {
+    find_invalid_ids(
+        range,
+        &RepetitionPolicy::Exactly(repetitions),
+        Radix::DECIMAL,
+    )
+}
+
+/// One way of writing an ID as a unit repeated
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Repetition {
+    pub unit: Id,
+    pub times: u32,
+}
+
+/// Every way the ID is a unit repeated at least twice, shortest unit first
+pub fn repetitions(id: Id, radix: Radix) -> Vec<Repetition> {
+    let digits = radix.digits(id);
 
-    // In the old version we went through some pains to calculate the right "first" prefix. We could
-    // do that again but we might as well skip through them
+    divisors(digits)
+        .filter(|unit_length| digits / unit_length >= 2)
+        .map(|unit_length| Repetition {
+            unit: id / radix.pow(digits - unit_length),
+            times: digits / unit_length,
+        })
+        .filter(|r| invalid_id(r.unit, r.times, radix) == Some(id))
+        .collect()
+}
 
-    (first_prefix..)
-        .take_while(move |prefix| prefix.ilog10() == prefix_length - 1)
-        .map(move |prefix| invalid_id(prefix, repetitions))
+/// Why an ID is invalid, for checking the answers by eye
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Explanation {
+    pub id: Id,
+    pub radix: Radix,
+    /// Empty for palindromes that aren't also repeats
+    pub repetitions: Vec<Repetition>,
 }
 
-fn invalid_ids_with_prefix_length(
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.radix.format(self.id))?;
+
+        if self.repetitions.is_empty() {
+            return write!(f, " reads the same backwards");
+        }
+        for r in &self.repetitions {
+            write!(f, " = {:?} x{}", self.radix.format(r.unit), r.times)?;
+        }
+
+        Ok(())
+    }
+}
+
+pub fn explain(
     range: &IdRange,
-    prefix_length: u32,
-) -> impl Iterator<Item = u64> {
-    // Repeated ranges of characters can only happen when the number of digits divide by len, and
-    // there's only one for a given "prefix". We will generate them directly.
-    let digits = range.start().ilog10() + 1;
+    policy: &RepetitionPolicy,
+    radix: Radix,
+) -> impl Iterator<Item = Explanation> + use<> {
+    find_invalid_ids(range, policy, radix).map(move |id| Explanation {
+        id,
+        radix,
+        repetitions: repetitions(id, radix),
+    })
+}
+
+/// Each range followed by its invalid IDs and how they're made
+pub fn format_explanations(ranges: &[IdRange], policy: &RepetitionPolicy, radix: Radix) -> String {
+    ranges
+        .iter()
+        .map(|range| {
+            let explanations = explain(range, policy, radix)
+                .map(|e| format!("  {}", e))
+                .collect::<Vec<_>>();
+
+            format!(
+                "{}-{}\n{}",
+                radix.format(*range.start()),
+                radix.format(*range.end()),
+                if explanations.is_empty() {
+                    "  (none)".to_string()
+                } else {
+                    explanations.join("\n")
+                }
+            )
+        })
+        .join("\n")
+}
 
-    // We could do better here but lazy
-    let first_repetitions = max(2, digits / prefix_length);
+/// The prefix repeated, or nothing if that's too big for an [Id]
+pub fn invalid_id(prefix: Id, repetitions: u32, radix: Radix) -> Option<Id> {
+    let length = radix.digits(prefix);
 
-    // In the old version we went through some pains to calculate the right "first" prefix. We could
-    // do that again but we might as well skip through them
+    (0..repetitions).try_fold(0, |id: Id, r| {
+        radix
+            .checked_pow(r * length)?
+            .checked_mul(prefix)?
+            .checked_add(id)
+    })
+}
+
+/// The palindrome with this many digits which starts with the prefix, which needs to be half of
+/// them rounding up
+pub fn palindrome(prefix: Id, digits: u32, radix: Radix) -> Option<Id> {
+    let back_digits = digits / 2;
+    let mut back = prefix / radix.pow(digits - 2 * back_digits);
+    let mut id = prefix.checked_mul(radix.checked_pow(back_digits)?)?;
+
+    for place in (0..back_digits).rev() {
+        id = id.checked_add((back % radix.0 as Id) * radix.pow(place))?;
+        back /= radix.0 as Id;
+    }
 
-    (first_repetitions..)
-        .flat_map(move |r| invalid_ids_with_prefix_and_repetitions(prefix_length, r))
-        .take_while(|id| range.end() >= id)
-        .filter(|id| range.contains(id))
+    Some(id)
 }
 
-fn invalid_id(prefix: u64, repetitions: u32) -> u64 {
-    let length = prefix.ilog10() + 1;
+/// Ranges like `11-22` separated by commas, whitespace or both, over as many lines as you like.
+/// Anything after a `#` on a line is a comment and a trailing comma is fine, but a comma needs a
+/// range before it.
+pub fn parse_ranges(str: &str, radix: Radix) -> Result<Vec<IdRange>, ParseError> {
+    let mut ranges = vec![];
+    // So `1-2,,3-4` and a comma at the start get caught
+    let mut comma_allowed = false;
+
+    for (line_number, line) in str.lines().enumerate() {
+        let line: Vec<char> = line.split('#').next().unwrap_or_default().chars().collect();
+        let is_separator = |c: char| c == ',' || c.is_whitespace();
+        let mut column = 0;
+
+        while column < line.len() {
+            if line[column] == ',' {
+                if !comma_allowed {
+                    return Err(ParseError::new(
+                        line_number + 1,
+                        column + 1,
+                        "expected a range before ','",
+                    ));
+                }
+                comma_allowed = false;
+                column += 1;
+            } else if line[column].is_whitespace() {
+                column += 1;
+            } else {
+                let end = (column..line.len())
+                    .find(|i| is_separator(line[*i]))
+                    .unwrap_or(line.len());
+                let range: String = line[column..end].iter().collect();
+
+                ranges
+                    .push(parse_range(&range, radix).map_err(|e| e.shifted(line_number, column))?);
+                comma_allowed = true;
+                column = end;
+            }
+        }
+    }
 
-    (0..repetitions)
-        .map(|r| prefix * 10u64.pow(r * length))
-        .sum()
+    Ok(ranges)
 }
 
-fn parse_ranges(str: &str) -> Result<Vec<IdRange>, Box<dyn Error>> {
-    str.split(",").map(parse_range).collect()
+/// A single range like `11-22`, which has to be the right way round
+pub fn parse_range(str: &str, radix: Radix) -> Result<IdRange, ParseError> {
+    let Some((start, end)) = str.split_once('-') else {
+        return Err(ParseError::new(
+            1,
+            1,
+            format!("expected a range like 11-22, found {:?}", str),
+        ));
+    };
+    let end_column = start.chars().count() + 2;
+
+    if let Some(dash) = end.find('-') {
+        return Err(ParseError::new(
+            1,
+            end_column + end[..dash].chars().count(),
+            "a range has one '-', between its first and last IDs",
+        ));
+    }
+    if start.is_empty() {
+        return Err(ParseError::new(1, 1, "missing first ID of range"));
+    }
+    if end.is_empty() {
+        return Err(ParseError::new(1, end_column, "missing last ID of range"));
+    }
+
+    let first = parse_id(start, radix)?;
+    let last = parse_id(end, radix).map_err(|e| e.shifted(0, end_column - 1))?;
+
+    if first > last {
+        return Err(ParseError::new(
+            1,
+            1,
+            format!("range {:?} ends before it starts", str),
+        ));
+    }
+
+    Ok(first..=last)
 }
 
-fn parse_range(str: &str) -> Result<IdRange, Box<dyn Error>> {
-    let bits: Vec<u64> = str
-        .split("-")
-        .map(u64::from_str)
-        .collect::<Result<Vec<_>, ParseIntError>>()?;
+fn parse_id(str: &str, radix: Radix) -> Result<Id, ParseError> {
+    // from_str_radix is happy with a plus sign, but IDs are just digits
+    if str.starts_with('+') {
+        return Err(ParseError::new(
+            1,
+            1,
+            format!("invalid ID {:?}: IDs don't have a sign", str),
+        ));
+    }
+
+    let id = radix
+        .parse(str)
+        .map_err(|e| ParseError::new(1, 1, format!("invalid ID {:?}: {}", str, e)))?;
 
-    if bits.len() != 2 {
-        return Err("encountered range with more than two components".into());
+    // Zero has no digits as far as ilog is concerned, and isn't much of an ID anyway
+    if id == 0 {
+        return Err(ParseError::new(1, 1, "IDs must be positive"));
     }
 
-    Ok(bits[0]..=bits[1])
+    Ok(id)
 }
 
 #[cfg(test)]
 mod tests {
     use crate::day2::*;
 
-    static EXAMPLES: &'static str = "11-22,\
+    static EXAMPLES: &str = "11-22,\
         95-115,\
         998-1012,\
         1188511880-1188511890,\
@@ -155,22 +716,322 @@ mod tests {
     #[test]
     fn parse_ranges_simple() {
         assert_eq!(
-            parse_ranges("11-22,95-115,998-1012,1188511880-1188511890").unwrap(),
+            parse_ranges(
+                "11-22,95-115,998-1012,1188511880-1188511890",
+                Radix::DECIMAL
+            )
+            .unwrap(),
             vec![11..=22, 95..=115, 998..=1012, 1188511880..=1188511890]
         );
     }
 
+    #[test]
+    fn parse_ranges_errors() {
+        assert_eq!(
+            parse_ranges("11-22,95-1x5", Radix::DECIMAL).unwrap_err(),
+            ParseError::new(1, 10, "invalid ID \"1x5\": invalid digit found in string")
+        );
+        assert_eq!(
+            parse_ranges("11-22,0-5", Radix::DECIMAL).unwrap_err(),
+            ParseError::new(1, 7, "IDs must be positive")
+        );
+        assert_eq!(
+            parse_ranges("1-2-3", Radix::DECIMAL).unwrap_err(),
+            ParseError::new(1, 4, "a range has one '-', between its first and last IDs")
+        );
+    }
+
+    #[test]
+    fn parse_ranges_tolerates_layout() {
+        let ranges = parse_ranges(
+            "# The example, more or less\n\
+             11-22, 95-115 ,\r\n\
+             \t998-1012   1188511880-1188511890 # the big one\n\
+             \n\
+             222220-222224,\n",
+            Radix::DECIMAL,
+        )
+        .unwrap();
+
+        assert_eq!(
+            ranges,
+            vec![
+                11..=22,
+                95..=115,
+                998..=1012,
+                1188511880..=1188511890,
+                222220..=222224
+            ]
+        );
+        assert_eq!(parse_ranges(" \n# nothing\n", Radix::DECIMAL), Ok(vec![]));
+    }
+
+    #[test]
+    fn parse_ranges_precise_errors() {
+        let error = |input| parse_ranges(input, Radix::DECIMAL).unwrap_err();
+
+        assert_eq!(
+            error("11-22,\n95-115,22-11"),
+            ParseError::new(2, 8, "range \"22-11\" ends before it starts")
+        );
+        assert_eq!(
+            error("11-22,,95-115"),
+            ParseError::new(1, 7, "expected a range before ','")
+        );
+        assert_eq!(
+            error("  , 11-22"),
+            ParseError::new(1, 3, "expected a range before ','")
+        );
+        assert_eq!(
+            error("11-22 95"),
+            ParseError::new(1, 7, "expected a range like 11-22, found \"95\"")
+        );
+        assert_eq!(
+            error("11-22\n-5"),
+            ParseError::new(2, 1, "missing first ID of range")
+        );
+        assert_eq!(
+            error("11-22 95- 115"),
+            ParseError::new(1, 10, "missing last ID of range")
+        );
+        assert_eq!(
+            error("11-22\n\n  1-x # comment"),
+            ParseError::new(3, 5, "invalid ID \"x\": invalid digit found in string")
+        );
+        assert_eq!(
+            error("11-22 5-+7"),
+            ParseError::new(1, 9, "invalid ID \"+7\": IDs don't have a sign")
+        );
+        assert_eq!(
+            error("+5-7"),
+            ParseError::new(1, 1, "invalid ID \"+5\": IDs don't have a sign")
+        );
+    }
+
+    #[test]
+    fn overlapping_ranges_counted_once() {
+        let ranges = parse_ranges("11-22 20-100 200-300 95-99 1000-1010", Radix::DECIMAL).unwrap();
+        let policy = RepetitionPolicy::Exactly(2);
+
+        assert_eq!(overlapping_ranges(&ranges), vec![(0, 1), (1, 3)]);
+        assert_eq!(
+            normalise_ranges(&ranges),
+            vec![11..=100, 200..=300, 1000..=1010]
+        );
+        assert_eq!(
+            sum_all_invalid_ids(&ranges, &policy, Radix::DECIMAL).unwrap(),
+            (11 + 22) + (22 + 33 + 44 + 55 + 66 + 77 + 88 + 99) + 99 + 1010
+        );
+        assert_eq!(
+            sum_all_invalid_ids(&normalise_ranges(&ranges), &policy, Radix::DECIMAL).unwrap(),
+            (11 + 22 + 33 + 44 + 55 + 66 + 77 + 88 + 99) + 1010
+        );
+        assert_eq!(
+            format_overlaps(&ranges, Radix::DECIMAL),
+            "11-22 (range 1) overlaps 20-100 (range 2)\n\
+             20-100 (range 2) overlaps 95-99 (range 4)"
+        );
+    }
+
+    #[test]
+    fn explanations() {
+        assert_eq!(
+            repetitions(222222, Radix::DECIMAL),
+            vec![
+                Repetition { unit: 2, times: 6 },
+                Repetition { unit: 22, times: 3 },
+                Repetition {
+                    unit: 222,
+                    times: 2
+                },
+            ]
+        );
+        assert_eq!(repetitions(123, Radix::DECIMAL), vec![]);
+
+        let explained = |range, policy| {
+            explain(&range, &policy, Radix::DECIMAL)
+                .map(|e| e.to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            explained(824824821..=824824827, RepetitionPolicy::AtLeast(2)),
+            vec!["824824824 = \"824\" x3"]
+        );
+        assert_eq!(
+            explained(222220..=222224, RepetitionPolicy::Exactly(2)),
+            vec!["222222 = \"2\" x6 = \"22\" x3 = \"222\" x2"]
+        );
+        assert_eq!(
+            explained(120..=122, RepetitionPolicy::Palindromic),
+            vec!["121 reads the same backwards"]
+        );
+
+        assert_eq!(
+            format_explanations(
+                &[0xa..=0x1f, 0x100..=0x102],
+                &RepetitionPolicy::AtLeast(2),
+                Radix::HEX
+            ),
+            "a-1f\n  11 = \"1\" x2\n100-102\n  (none)"
+        );
+    }
+
     #[test]
     fn invalid_id_various_lengths() {
-        assert_eq!(invalid_id(21, 4), 21212121);
-        assert_eq!(invalid_id(123, 4), 123123123123);
-        assert_eq!(invalid_id(1234, 2), 12341234);
+        assert_eq!(invalid_id(21, 4, Radix::DECIMAL), Some(21212121));
+        assert_eq!(invalid_id(123, 4, Radix::DECIMAL), Some(123123123123));
+        assert_eq!(invalid_id(1234, 2, Radix::DECIMAL), Some(12341234));
+    }
+
+    #[test]
+    fn invalid_id_beyond_u64() {
+        assert_eq!(
+            invalid_id(1844674407370955161, 2, Radix::DECIMAL),
+            Some(18446744073709551611844674407370955161)
+        );
+        assert_eq!(invalid_id(18446744073709551615, 2, Radix::DECIMAL), None);
+        assert_eq!(invalid_id(340, 13, Radix::DECIMAL), None);
+        assert!(contains_repetition(339339339339339339339339339339339339339));
+        assert!(!contains_repetition(u128::MAX));
+    }
+
+    #[test]
+    fn ranges_beyond_u64() {
+        let range = 99999999999999999999..=100000000000000000000000;
+
+        assert_eq!(
+            find_all_invalid_ids_with_exact_repetition(&range, 2)
+                .take(3)
+                .collect::<Vec<_>>(),
+            vec![
+                99999999999999999999,
+                1000000000010000000000,
+                1000000000110000000001
+            ]
+        );
+        assert_eq!(
+            find_all_invalid_ids(&(u128::MAX - 1_000_000_000..=u128::MAX)).count(),
+            0
+        );
+        assert_eq!(
+            find_all_invalid_ids(
+                &(339339339339339339339339339339339339000
+                    ..=339339339339339339339339339339339339999)
+            )
+            .collect::<Vec<_>>(),
+            vec![339339339339339339339339339339339339339]
+        );
+    }
+
+    #[test]
+    fn closed_form_matches_generated() {
+        let mut ranges = parse_ranges(EXAMPLES, Radix::DECIMAL).unwrap();
+        ranges.extend([
+            1..=100_000,
+            1..=9,
+            95..=95,
+            222222..=222222,
+            9_000_000..=12_000_000,
+        ]);
+
+        for range in &ranges {
+            let exactly =
+                |k| find_all_invalid_ids_with_exact_repetition(range, k).collect::<Vec<_>>();
+            let at_least = find_all_invalid_ids(range).collect::<Vec<_>>();
+
+            for (policy, ids) in [
+                (RepetitionPolicy::Exactly(2), exactly(2)),
+                (RepetitionPolicy::Exactly(3), exactly(3)),
+                (RepetitionPolicy::AtLeast(2), at_least),
+            ] {
+                assert_eq!(
+                    sum_invalid_ids(range, &policy, Radix::DECIMAL).unwrap(),
+                    ids.iter().sum::<Id>(),
+                    "{:?} {:?}",
+                    range,
+                    policy
+                );
+                assert_eq!(
+                    count_invalid_ids(range, &policy, Radix::DECIMAL),
+                    ids.len() as Id
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn closed_form_wide_ranges() {
+        let range = 1..=Id::pow(10, 18);
+
+        // One for every unit of length 1 to 9
+        assert_eq!(
+            count_invalid_ids(&range, &RepetitionPolicy::Exactly(2), Radix::DECIMAL),
+            999_999_999
+        );
+        assert!(sum_invalid_ids(&range, &RepetitionPolicy::AtLeast(2), Radix::DECIMAL).is_ok());
+        assert_eq!(
+            count_invalid_ids(&(1..=99), &RepetitionPolicy::AtLeast(2), Radix::DECIMAL),
+            9
+        );
+        assert!(matches!(
+            sum_invalid_ids(
+                &(1..=Id::MAX),
+                &RepetitionPolicy::AtLeast(2),
+                Radix::DECIMAL
+            ),
+            Err(Error::InvalidPuzzle(_))
+        ));
+    }
+
+    #[test]
+    fn closed_form_near_the_top_in_every_radix() {
+        let range = Id::MAX / 2..=Id::MAX;
+
+        for radix in (2..=36).map(Radix) {
+            for policy in [
+                RepetitionPolicy::Exactly(2),
+                RepetitionPolicy::AtLeast(2),
+                RepetitionPolicy::UnitLengths(BTreeSet::from([1, 2, 3])),
+                RepetitionPolicy::Palindromic,
+            ] {
+                count_invalid_ids(&range, &policy, radix);
+                let _ = sum_invalid_ids(&range, &policy, radix);
+            }
+        }
+
+        // Every 64 bit unit starting with a 1 doubled up
+        assert_eq!(
+            count_invalid_ids(&range, &RepetitionPolicy::Exactly(2), Radix(2)),
+            1 << 63
+        );
+    }
+
+    #[test]
+    fn overflowing_sum_is_an_error() {
+        let ranges = parse_ranges(
+            "339339339339339339339339339339339339339-339339339339339339339339339339339339339,\
+             339339339339339339339339339339339339339-339339339339339339339339339339339339339",
+            Radix::DECIMAL,
+        )
+        .unwrap();
+
+        assert!(matches!(Day2::part2(&ranges), Err(Error::InvalidPuzzle(_))));
+        assert_eq!(
+            parse_ranges("1-340282366920938463463374607431768211456", Radix::DECIMAL).unwrap_err(),
+            ParseError::new(
+                1,
+                3,
+                "invalid ID \"340282366920938463463374607431768211456\": \
+                 number too large to fit in target type"
+            )
+        );
     }
 
     #[test]
-    fn invalid_ids_with_prefix_length_works_as_expected() {
-        fn collect_ids(range: &IdRange, prefix_length: u32) -> Vec<u64> {
-            invalid_ids_with_prefix_length(range, prefix_length).collect()
+    fn unit_lengths_policy_works_as_expected() {
+        fn collect_ids(range: &IdRange, prefix_length: u32) -> Vec<Id> {
+            let policy = RepetitionPolicy::UnitLengths(BTreeSet::from([prefix_length]));
+            find_invalid_ids(range, &policy, Radix::DECIMAL).collect()
         }
 
         assert_eq!(collect_ids(&(11..=44), 1), vec![11, 22, 33, 44]);
@@ -191,11 +1052,132 @@ mod tests {
         );
     }
 
+    #[test]
+    fn palindromes() {
+        assert_eq!(palindrome(12, 4, Radix::DECIMAL), Some(1221));
+        assert_eq!(palindrome(123, 5, Radix::DECIMAL), Some(12321));
+        assert_eq!(palindrome(9, 2, Radix::DECIMAL), Some(99));
+        assert_eq!(palindrome(Id::pow(10, 19), 40, Radix::DECIMAL), None);
+
+        let policy = RepetitionPolicy::Palindromic;
+        assert_eq!(
+            find_invalid_ids(&(1..=130), &policy, Radix::DECIMAL).collect::<Vec<_>>(),
+            vec![11, 22, 33, 44, 55, 66, 77, 88, 99, 101, 111, 121]
+        );
+        assert_eq!(
+            count_invalid_ids(&(1..=Id::pow(10, 6)), &policy, Radix::DECIMAL),
+            9 + 90 + 90 + 900 + 900
+        );
+        // Far more than a usize can count
+        assert_eq!(
+            count_invalid_ids(&(1..=Id::pow(10, 38) - 1), &policy, Radix::DECIMAL),
+            2 * (Id::pow(10, 19) - 1) - 9
+        );
+        assert!(count_invalid_ids(&(1..=Id::MAX), &policy, Radix::DECIMAL) > Id::pow(10, 19));
+        assert_eq!(
+            sum_invalid_ids(&(1..=130), &policy, Radix::DECIMAL).unwrap(),
+            (1..=9).sum::<Id>() * 11 + 101 + 111 + 121
+        );
+    }
+
+    #[test]
+    fn generated_matches_brute_force_for_every_policy() {
+        let policies = [
+            RepetitionPolicy::Exactly(2),
+            RepetitionPolicy::Exactly(3),
+            RepetitionPolicy::AtLeast(2),
+            RepetitionPolicy::AtLeast(3),
+            RepetitionPolicy::UnitLengths(BTreeSet::from([1, 3])),
+            RepetitionPolicy::UnitLengths(BTreeSet::from([2])),
+            RepetitionPolicy::Palindromic,
+        ];
+        let mut ranges = parse_ranges(EXAMPLES, Radix::DECIMAL).unwrap();
+        ranges.extend([1..=200_000, 5..=5, 999_000..=1_001_000]);
+
+        for policy in &policies {
+            for range in &ranges {
+                let generated: Vec<Id> = find_invalid_ids(range, policy, Radix::DECIMAL).collect();
+
+                assert_eq!(
+                    generated,
+                    find_invalid_ids_brute_force(range, policy, Radix::DECIMAL).collect::<Vec<_>>(),
+                    "{:?} {:?}",
+                    range,
+                    policy
+                );
+                assert_eq!(
+                    count_invalid_ids(range, policy, Radix::DECIMAL),
+                    generated.len() as Id
+                );
+                assert_eq!(
+                    sum_invalid_ids(range, policy, Radix::DECIMAL).unwrap(),
+                    generated.iter().sum::<Id>()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn other_radixes() {
+        assert_eq!(Radix::new(1), None);
+        assert_eq!(Radix::new(37), None);
+        assert_eq!(Radix::new(16), Some(Radix::HEX));
+        assert_eq!(Radix::HEX.format(0x1212), "1212");
+        assert_eq!(Radix::BASE36.format(1295), "zz");
+        assert_eq!(Radix::BASE36.parse("ZZ"), Ok(1295));
+        assert_eq!(Radix::DECIMAL.format(0), "0");
+
+        assert!(RepetitionPolicy::Exactly(2).accepts(0x1212, Radix::HEX));
+        assert!(!RepetitionPolicy::Exactly(2).accepts(0x1212, Radix::DECIMAL));
+        assert_eq!(invalid_id(0xab, 3, Radix::HEX), Some(0xababab));
+        assert_eq!(palindrome(0x1f, 3, Radix::HEX), Some(0x1f1));
+
+        let ranges = parse_ranges("a-1f,fe-102", Radix::HEX).unwrap();
+        assert_eq!(ranges, vec![0xa..=0x1f, 0xfe..=0x102]);
+        assert_eq!(
+            ranges
+                .iter()
+                .flat_map(|r| find_invalid_ids(r, &RepetitionPolicy::AtLeast(2), Radix::HEX))
+                .collect::<Vec<_>>(),
+            vec![0x11, 0xff]
+        );
+        assert_eq!(
+            format_totals(&ranges, &[2], Radix::HEX).unwrap(),
+            "Part | Invalid IDs | Sum\n\
+             -----+-------------+----\n\
+             2    | 2           | 110"
+        );
+        assert_eq!(
+            parse_ranges("a-1g", Radix::HEX).unwrap_err(),
+            ParseError::new(1, 3, "invalid ID \"1g\": invalid digit found in string")
+        );
+    }
+
+    #[test]
+    fn closed_form_in_other_radixes() {
+        let range = 1..=20_000;
+
+        for radix in [Radix(2), Radix(3), Radix::HEX, Radix::BASE36] {
+            for policy in [RepetitionPolicy::Exactly(2), RepetitionPolicy::AtLeast(2)] {
+                let ids: Vec<Id> = find_invalid_ids_brute_force(&range, &policy, radix).collect();
+
+                assert_eq!(
+                    sum_invalid_ids(&range, &policy, radix).unwrap(),
+                    ids.iter().sum::<Id>(),
+                    "{:?} {:?}",
+                    radix,
+                    policy
+                );
+                assert_eq!(count_invalid_ids(&range, &policy, radix), ids.len() as Id);
+            }
+        }
+    }
+
     #[test]
     fn exactly_2_repetitions_examples() {
-        let examples = parse_ranges(EXAMPLES).unwrap();
+        let examples = parse_ranges(EXAMPLES, Radix::DECIMAL).unwrap();
 
-        let invalid_ids: Vec<Vec<u64>> = examples
+        let invalid_ids: Vec<Vec<Id>> = examples
             .iter()
             .map(|e| find_all_invalid_ids_with_exact_repetition(e, 2).collect())
             .collect();
@@ -220,8 +1202,8 @@ mod tests {
         assert_eq!(
             examples
                 .iter()
-                .map(|r| find_all_invalid_ids_with_exact_repetition(r, 2).sum::<u64>())
-                .sum::<u64>(),
+                .map(|r| find_all_invalid_ids_with_exact_repetition(r, 2).sum::<Id>())
+                .sum::<Id>(),
             1227775554
         );
     }
@@ -229,7 +1211,7 @@ mod tests {
     #[test]
     fn all_repetitions_duplicates_returned_once() {
         assert_eq!(
-            find_all_invalid_ids(&(1111..=1111)).collect::<Vec<u64>>(),
+            find_all_invalid_ids(&(1111..=1111)).collect::<Vec<Id>>(),
             vec![1111]
         );
     }
@@ -237,16 +1219,16 @@ mod tests {
     #[test]
     fn all_repetitions_single_digits() {
         assert_eq!(
-            find_all_invalid_ids(&(1..=20)).collect::<Vec<u64>>(),
+            find_all_invalid_ids(&(1..=20)).collect::<Vec<Id>>(),
             vec![11]
         );
     }
 
     #[test]
     fn all_repetitions_examples() {
-        let examples = parse_ranges(EXAMPLES).unwrap();
+        let examples = parse_ranges(EXAMPLES, Radix::DECIMAL).unwrap();
 
-        let invalid_ids: Vec<Vec<u64>> = examples
+        let invalid_ids: Vec<Vec<Id>> = examples
             .iter()
             .map(|e| find_all_invalid_ids(e).collect())
             .collect();
@@ -271,8 +1253,8 @@ mod tests {
         assert_eq!(
             examples
                 .iter()
-                .map(|r| find_all_invalid_ids(r).sum::<u64>())
-                .sum::<u64>(),
+                .map(|r| find_all_invalid_ids(r).sum::<Id>())
+                .sum::<Id>(),
             4174379265
         );
     }
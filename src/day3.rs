@@ -1,59 +1,89 @@
-use std::error::Error;
-use std::fs::File;
-use std::io;
-use std::io::BufRead;
-use std::path::Path;
+use crate::error::{Error, ParseError, Result};
+use crate::solution::{Answer, Puzzle};
 
-pub fn part1() -> Result<u64, Box<dyn Error>> {
-    let banks = read_banks("inputs/day3.part1.txt")?;
+pub struct Day3;
 
-    let result: u64 = banks.iter().map(|b| b.max_joltage(2)).sum();
+impl Puzzle for Day3 {
+    const DAY: u32 = 3;
 
-    Ok(result)
-}
+    type Input = Vec<Bank>;
 
-pub fn part2() -> Result<u64, Box<dyn Error>> {
-    let banks = read_banks("inputs/day3.part1.txt")?;
+    fn parse(input: &str) -> Result<Vec<Bank>> {
+        Ok(parse_banks(input)?)
+    }
 
-    let result: u64 = banks.iter().map(|b| b.max_joltage(12)).sum();
+    fn part1(banks: &Vec<Bank>) -> Result<Answer> {
+        Ok(Box::new(total_joltage(banks, 2)?))
+    }
 
-    Ok(result)
+    fn part2(banks: &Vec<Bank>) -> Result<Answer> {
+        Ok(Box::new(total_joltage(banks, 12)?))
+    }
 }
 
-fn read_banks<P>(filename: P) -> io::Result<Vec<Bank>>
-where
-    P: AsRef<Path>,
-{
-    let file = File::open(filename)?;
-
-    io::BufReader::new(file)
-        .lines()
-        .map(|l| l.map(|t| Bank::parse(t.as_str())))
+pub fn parse_banks(input: &str) -> Result<Vec<Bank>, ParseError> {
+    // Same as day 4, blank lines at the end aren't empty banks
+    let lines: Vec<&str> = input.lines().collect();
+    let n_banks = lines
+        .iter()
+        .rposition(|line| !line.trim().is_empty())
+        .map_or(0, |last| last + 1);
+
+    lines[..n_banks]
+        .iter()
+        .enumerate()
+        .map(|(i, line)| Bank::parse(line).map_err(|e| e.shifted(i, 0)))
         .collect()
 }
 
+pub fn total_joltage(banks: &[Bank], length: usize) -> Result<u64> {
+    banks
+        .iter()
+        .enumerate()
+        .map(|(i, b)| {
+            b.max_joltage(length).ok_or_else(|| {
+                Error::InvalidPuzzle(format!(
+                    "bank {} has {} batteries but {} need turning on",
+                    i + 1,
+                    b.0.len(),
+                    length
+                ))
+            })
+        })
+        .sum()
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
-struct Bank(Vec<u32>);
+pub struct Bank(Vec<u32>);
 
 impl Bank {
-    fn new(batteries: Vec<u32>) -> Bank {
+    pub fn new(batteries: Vec<u32>) -> Bank {
         Bank(batteries)
     }
 
-    fn parse(str: &str) -> Bank {
+    pub fn parse(str: &str) -> Result<Bank, ParseError> {
         let batteries = str
             .chars()
-            .map(|c| c.to_digit(10).unwrap())
-            .collect::<Vec<_>>();
-        Bank::new(batteries)
+            .enumerate()
+            .map(|(i, c)| {
+                c.to_digit(10).ok_or_else(|| {
+                    ParseError::new(1, i + 1, format!("expected a joltage digit, found {:?}", c))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Bank::new(batteries))
     }
 
-    fn max_joltage(&self, length: usize) -> u64 {
-        max_joltage(&self.0, length)
+    pub fn max_joltage(&self, length: usize) -> Option<u64> {
+        if self.0.len() < length {
+            None
+        } else {
+            Some(max_joltage(&self.0, length))
+        }
     }
 }
 
-fn max_joltage(window: &[u32], length: usize) -> u64 {
+pub fn max_joltage(window: &[u32], length: usize) -> u64 {
     assert!(length > 0);
 
     // We need to have at least length digits left after we select one
@@ -82,7 +112,10 @@ mod tests {
 
     #[test]
     pub fn max_joltage_examples() {
-        let max_joltages = EXAMPLES.map(Bank::parse).map(|b| b.max_joltage(2)).to_vec();
+        let max_joltages = EXAMPLES
+            .map(|e| Bank::parse(e).unwrap())
+            .map(|b| b.max_joltage(2).unwrap())
+            .to_vec();
 
         assert_eq!(max_joltages, vec![98, 89, 78, 92]);
     }
@@ -90,8 +123,8 @@ mod tests {
     #[test]
     pub fn max_joltage_12_examples() {
         let max_joltages = EXAMPLES
-            .map(Bank::parse)
-            .map(|b| b.max_joltage(12))
+            .map(|e| Bank::parse(e).unwrap())
+            .map(|b| b.max_joltage(12).unwrap())
             .to_vec();
 
         assert_eq!(
@@ -104,7 +137,30 @@ mod tests {
     pub fn parse_example() {
         assert_eq!(
             Bank::parse("987654321111111"),
-            Bank::new(vec![9, 8, 7, 6, 5, 4, 3, 2, 1, 1, 1, 1, 1, 1, 1])
+            Ok(Bank::new(vec![9, 8, 7, 6, 5, 4, 3, 2, 1, 1, 1, 1, 1, 1, 1]))
         );
+        assert_eq!(
+            parse_banks("987\n12\n\n  \n"),
+            Ok(vec![Bank::new(vec![9, 8, 7]), Bank::new(vec![1, 2])])
+        );
+    }
+
+    #[test]
+    pub fn parse_errors() {
+        assert_eq!(
+            parse_banks("12\n3?4\n").unwrap_err(),
+            ParseError::new(2, 2, "expected a joltage digit, found '?'")
+        );
+    }
+
+    #[test]
+    pub fn short_bank_is_invalid() {
+        let banks = parse_banks("987\n9\n").unwrap();
+
+        assert_eq!(total_joltage(&banks, 1).unwrap(), 18);
+        assert!(matches!(
+            total_joltage(&banks, 2),
+            Err(Error::InvalidPuzzle(_))
+        ));
     }
 }
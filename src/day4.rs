@@ -1,30 +1,32 @@
+use crate::error::{ParseError, Result};
+use crate::solution::{Answer, Puzzle};
 use array2d::Array2D;
-use std::error::Error;
 use std::fmt::Debug;
-use std::fs::File;
-use std::io::Read;
+use std::str::FromStr;
 
-pub fn part1() -> Result<usize, Box<dyn Error>> {
-    let mut input = String::new();
-    File::open("inputs/day4.part1.txt")?.read_to_string(&mut input)?;
+pub struct Day4;
 
-    let grid = Grid::from_str(input.as_str());
+impl Puzzle for Day4 {
+    const DAY: u32 = 4;
 
-    Ok(grid.accessible_rolls().len())
-}
+    type Input = Grid;
 
-pub fn part2() -> Result<usize, Box<dyn Error>> {
-    let mut input = String::new();
-    File::open("inputs/day4.part1.txt")?.read_to_string(&mut input)?;
+    fn parse(input: &str) -> Result<Grid> {
+        Ok(Grid::from_str(input)?)
+    }
 
-    let mut grid = Grid::from_str(input.as_str());
+    fn part1(grid: &Grid) -> Result<Answer> {
+        Ok(Box::new(grid.accessible_rolls().len()))
+    }
 
-    Ok(grid.remove_accessible_repeated())
+    fn part2(grid: &Grid) -> Result<Answer> {
+        Ok(Box::new(grid.clone().remove_accessible_repeated()))
+    }
 }
 
 // These have stupid names so they have the same number of characters and I can line them up
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
-enum Entry {
+pub enum Entry {
     Empt,
     Roll,
 }
@@ -40,25 +42,55 @@ impl Entry {
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
-struct Grid(Array2D<Entry>);
+pub struct Grid(Array2D<Entry>);
 
-impl Grid {
-    fn from_str(str: &str) -> Self {
-        let n_rows = str.lines().count();
-        let n_columns = str.lines().next().unwrap().len();
+impl FromStr for Grid {
+    type Err = ParseError;
+
+    fn from_str(str: &str) -> Result<Self, ParseError> {
+        // Blank lines at the end are left over from piping or generating the grid, not rows
+        let lines: Vec<&str> = str.lines().collect();
+        let n_rows = lines
+            .iter()
+            .rposition(|line| !line.trim().is_empty())
+            .map_or(0, |last| last + 1);
+        let lines = &lines[..n_rows];
+
+        let n_columns = lines
+            .first()
+            .map(|line| line.chars().count())
+            .filter(|n| *n > 0)
+            .ok_or_else(|| ParseError::new(1, 1, "expected a grid, found nothing"))?;
 
         let mut array = Array2D::filled_by_column_major(|| Entry::Empt, n_rows, n_columns);
 
-        str.lines().enumerate().for_each(|(r, line)| {
-            line.chars().enumerate().for_each(|(c, chr)| {
-                array[(r, c)] = Entry::from_char(&chr).unwrap();
-            });
-        });
+        for (r, line) in lines.iter().enumerate() {
+            let width = line.chars().count();
+            if width != n_columns {
+                return Err(ParseError::new(
+                    r + 1,
+                    width.min(n_columns) + 1,
+                    format!("expected {} columns, found {}", n_columns, width),
+                ));
+            }
+
+            for (c, chr) in line.chars().enumerate() {
+                array[(r, c)] = Entry::from_char(&chr).ok_or_else(|| {
+                    ParseError::new(
+                        r + 1,
+                        c + 1,
+                        format!("expected '.' or '@', found {:?}", chr),
+                    )
+                })?;
+            }
+        }
 
-        Grid(array)
+        Ok(Grid(array))
     }
+}
 
-    fn remove_accessible_repeated(&mut self) -> usize {
+impl Grid {
+    pub fn remove_accessible_repeated(&mut self) -> usize {
         let mut total_removed: usize = 0;
 
         loop {
@@ -71,7 +103,7 @@ impl Grid {
         }
     }
 
-    fn remove_accessible_once(&mut self) -> usize {
+    pub fn remove_accessible_once(&mut self) -> usize {
         let mut count: usize = 0;
         for loc in self.accessible_rolls() {
             count += 1;
@@ -84,7 +116,7 @@ impl Grid {
         self.0[loc] = Entry::Empt;
     }
 
-    fn accessible_rolls(&self) -> Vec<(usize, usize)> {
+    pub fn accessible_rolls(&self) -> Vec<(usize, usize)> {
         self.0
             .indices_row_major()
             .filter(|loc| self.0[*loc] == Entry::Roll)
@@ -135,7 +167,7 @@ mod tests {
 
     #[test]
     fn parse_example() {
-        let grid = Grid::from_str(EXAMPLE);
+        let grid = Grid::from_str(EXAMPLE).unwrap();
 
         use Entry::*;
 
@@ -161,7 +193,7 @@ mod tests {
 
     #[test]
     fn part1_example() {
-        let grid = Grid::from_str(EXAMPLE);
+        let grid = Grid::from_str(EXAMPLE).unwrap();
 
         assert_eq!(
             grid.accessible_rolls(),
@@ -185,8 +217,40 @@ mod tests {
 
     #[test]
     fn part2_example() {
-        let mut grid = Grid::from_str(EXAMPLE);
+        let mut grid = Grid::from_str(EXAMPLE).unwrap();
 
         assert_eq!(grid.remove_accessible_repeated(), 43);
     }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            Grid::from_str("..@\n.x@\n").unwrap_err(),
+            ParseError::new(2, 2, "expected '.' or '@', found 'x'")
+        );
+        assert_eq!(
+            Grid::from_str("..@\n.@\n").unwrap_err(),
+            ParseError::new(2, 3, "expected 3 columns, found 2")
+        );
+        assert_eq!(
+            Grid::from_str("").unwrap_err(),
+            ParseError::new(1, 1, "expected a grid, found nothing")
+        );
+        assert_eq!(
+            Grid::from_str("..@\n\n.@.\n").unwrap_err(),
+            ParseError::new(2, 1, "expected 3 columns, found 0")
+        );
+    }
+
+    #[test]
+    fn parse_ignores_trailing_blank_lines() {
+        assert_eq!(
+            Grid::from_str("..@\n.@.\n\n  \n"),
+            Grid::from_str("..@\n.@.")
+        );
+        assert_eq!(
+            Grid::from_str("\n\n").unwrap_err(),
+            ParseError::new(1, 1, "expected a grid, found nothing")
+        );
+    }
 }
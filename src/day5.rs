@@ -1,91 +1,119 @@
+use crate::error::{ParseError, Result};
+use crate::solution::{Answer, Puzzle};
 use disjoint_sets::UnionFind;
 use itertools::Itertools;
 use std::collections::HashMap;
-use std::error::Error;
-use std::fs::File;
-use std::io::Read;
+use std::hash::Hash;
 use std::ops::RangeInclusive;
 use std::str::FromStr;
 
-pub fn part1() -> Result<usize, Box<dyn Error>> {
-    let mut input = String::new();
-    File::open("inputs/day5.part1.txt")?.read_to_string(&mut input)?;
+pub struct Day5;
 
-    let database = Database::from_str(input.as_str())?;
+impl Puzzle for Day5 {
+    const DAY: u32 = 5;
 
-    Ok(database.fresh_available_ingredients().count())
-}
+    type Input = Database;
 
-pub fn part2() -> Result<u64, Box<dyn Error>> {
-    let mut input = String::new();
-    File::open("inputs/day5.part1.txt")?.read_to_string(&mut input)?;
+    fn parse(input: &str) -> Result<Database> {
+        Ok(Database::from_str(input)?)
+    }
 
-    let database = Database::from_str(input.as_str())?;
+    fn part1(database: &Database) -> Result<Answer> {
+        Ok(Box::new(database.fresh_available_ingredients().count()))
+    }
 
-    Ok(database.count_fresh_ingredients())
+    fn part2(database: &Database) -> Result<Answer> {
+        Ok(Box::new(database.count_fresh_ingredients()))
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
-struct Database {
+pub struct Database {
     fresh_ingredients: Vec<RangeInclusive<u64>>,
     available_ingredients: Vec<u64>,
 }
 
 impl Database {
-    fn new(fresh_ingredients: Vec<RangeInclusive<u64>>, available_ingredients: Vec<u64>) -> Self {
+    pub fn new(
+        fresh_ingredients: Vec<RangeInclusive<u64>>,
+        available_ingredients: Vec<u64>,
+    ) -> Self {
         Database {
             fresh_ingredients: simplify_ranges(&fresh_ingredients),
             available_ingredients,
         }
     }
 
-    fn from_str(input: &str) -> Result<Self, Box<dyn Error>> {
+    pub fn fresh_available_ingredients(&self) -> impl Iterator<Item = u64> {
+        self.available_ingredients
+            .iter()
+            .copied()
+            .filter(|ingr| self.is_fresh(*ingr))
+    }
+
+    pub fn count_fresh_ingredients(&self) -> u64 {
+        count_included(&self.fresh_ingredients)
+    }
+
+    pub fn is_fresh(&self, ingredient_id: u64) -> bool {
+        self.fresh_ingredients
+            .iter()
+            .any(|range| range.contains(&ingredient_id))
+    }
+}
+
+impl FromStr for Database {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, ParseError> {
         // We could presumably do better by taking the order into account
         let (fresh_lines, available_lines) = input
             .lines()
-            .filter(|line| !line.is_empty())
-            .partition::<Vec<_>, _>(|line| line.contains('-'));
+            .enumerate()
+            .filter(|(_, line)| !line.is_empty())
+            .partition::<Vec<_>, _>(|(_, line)| line.contains('-'));
 
         let fresh = fresh_lines
             .iter()
-            .map(|l| parse_range(l))
+            .map(|(i, l)| parse_range(l).map_err(|e| e.shifted(*i, 0)))
             .collect::<Result<Vec<_>, _>>()?;
         let available = available_lines
             .iter()
-            .map(|l| u64::from_str(l))
+            .map(|(i, l)| parse_id(l).map_err(|e| e.shifted(*i, 0)))
             .collect::<Result<Vec<_>, _>>()?;
 
         Ok(Database::new(fresh, available))
     }
+}
 
-    fn fresh_available_ingredients(&self) -> impl Iterator<Item = u64> {
-        self.available_ingredients
-            .iter()
-            .copied()
-            .filter(|ingr| self.is_fresh(*ingr))
-    }
-
-    fn count_fresh_ingredients(&self) -> u64 {
-        count_included(&self.fresh_ingredients)
-    }
+pub fn parse_range(s: &str) -> Result<RangeInclusive<u64>, ParseError> {
+    let mut bits: Vec<u64> = vec![];
+    let mut column = 0;
 
-    fn is_fresh(&self, ingredient_id: u64) -> bool {
-        self.fresh_ingredients
-            .iter()
-            .any(|range| range.contains(&ingredient_id))
+    for bit in s.split("-") {
+        bits.push(parse_id(bit).map_err(|e| e.shifted(0, column))?);
+        column += bit.chars().count() + 1;
     }
-}
 
-fn parse_range(s: &str) -> Result<RangeInclusive<u64>, Box<dyn Error>> {
-    let bits: Vec<u64> = s.split("-").map(u64::from_str).collect::<Result<_, _>>()?;
     if bits.len() != 2 {
-        Err("Invalid number of fields".into())
+        Err(ParseError::new(1, 1, "Invalid number of fields"))
+    } else if bits[1] < bits[0] {
+        Err(ParseError::new(1, 1, "Range ends before it starts"))
     } else {
         Ok(bits[0]..=bits[1])
     }
 }
 
-fn simplify_ranges(ranges: &[RangeInclusive<u64>]) -> Vec<RangeInclusive<u64>> {
+fn parse_id(s: &str) -> Result<u64, ParseError> {
+    u64::from_str(s)
+        .map_err(|e| ParseError::new(1, 1, format!("Invalid ingredient ID {:?}: {}", s, e)))
+}
+
+/// Merges ranges which overlap, so each value is in at most one of them. Generic as day 2 wants it
+/// for its much bigger IDs.
+pub fn simplify_ranges<T: Ord + Copy + Hash>(
+    ranges: &[RangeInclusive<T>],
+) -> Vec<RangeInclusive<T>> {
     let overlap_pairs = ranges
         .iter()
         .tuple_combinations::<(_, _)>()
@@ -121,18 +149,18 @@ fn simplify_ranges(ranges: &[RangeInclusive<u64>]) -> Vec<RangeInclusive<u64>> {
         .collect()
 }
 
-fn count_included(ranges: &[RangeInclusive<u64>]) -> u64 {
+pub fn count_included(ranges: &[RangeInclusive<u64>]) -> u64 {
     ranges.iter().map(|r| (r.end() - r.start()) + 1).sum()
 }
 
-fn merge(ranges: &Vec<&RangeInclusive<u64>>) -> RangeInclusive<u64> {
+fn merge<T: Ord + Copy>(ranges: &Vec<&RangeInclusive<T>>) -> RangeInclusive<T> {
     let start = ranges.iter().map(|range| range.start()).min().unwrap();
     let end = ranges.iter().map(|range| range.end()).max().unwrap();
 
     *start..=*end
 }
 
-fn overlap(l: &RangeInclusive<u64>, r: &RangeInclusive<u64>) -> bool {
+pub fn overlap<T: Ord>(l: &RangeInclusive<T>, r: &RangeInclusive<T>) -> bool {
     (l.start() <= r.end() && l.end() >= r.start()) || (r.start() <= l.end() && r.end() >= l.start())
 }
 
@@ -163,6 +191,30 @@ mod tests {
         )
     }
 
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            Database::from_str("3-5\n10-14\n5-\n\n1\n").unwrap_err(),
+            ParseError::new(
+                3,
+                3,
+                "Invalid ingredient ID \"\": cannot parse integer from empty string"
+            )
+        );
+        assert_eq!(
+            Database::from_str("3-5\n\n1\nx\n").unwrap_err(),
+            ParseError::new(
+                4,
+                1,
+                "Invalid ingredient ID \"x\": invalid digit found in string"
+            )
+        );
+        assert_eq!(
+            Database::from_str("5-3\n").unwrap_err(),
+            ParseError::new(1, 1, "Range ends before it starts")
+        );
+    }
+
     #[test]
     fn fresh_available_ingredients_example() {
         let database = Database::from_str(EXAMPLE).unwrap();
@@ -0,0 +1,244 @@
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum Error {
+    /// Reading something failed, `source_name` is a path or `<stdin>`
+    Io {
+        source_name: String,
+        error: io::Error,
+    },
+    Parse(ParseError),
+    UnknownCommand(String),
+    /// The command line made sense word by word but not as a whole
+    Usage(String),
+    /// The input parsed fine but describes a puzzle that can't be solved
+    InvalidPuzzle(String),
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io { source_name, error } => write!(f, "{}: {}", source_name, error),
+            Error::Parse(e) => write!(f, "{}", e),
+            Error::UnknownCommand(command) => write!(f, "Unknown command: {}", command),
+            Error::Usage(message) => write!(f, "{}", message),
+            Error::InvalidPuzzle(message) => write!(f, "Invalid puzzle: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl Error {
+    /// Parse errors come out of the days knowing only where in the text they happened, this fills
+    /// in which text that was so they can be shown in context. Other errors are left alone.
+    pub fn with_source(self, source_name: &str, text: &str) -> Error {
+        match self {
+            Error::Parse(e) => Error::Parse(e.with_source(source_name, text)),
+            other => other,
+        }
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(e: ParseError) -> Self {
+        Error::Parse(e)
+    }
+}
+
+/// Lines and columns both count from 1, columns are in characters rather than bytes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+    pub source: Option<SourceContext>,
+}
+
+/// Where a [ParseError] came from, enough to show the offending line with a caret under it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SourceContext {
+    pub name: String,
+    pub line_text: Option<String>,
+}
+
+impl ParseError {
+    pub fn new<S>(line: usize, column: usize, message: S) -> ParseError
+    where
+        S: Into<String>,
+    {
+        ParseError {
+            line,
+            column,
+            message: message.into(),
+            source: None,
+        }
+    }
+
+    pub fn at_offset<S>(text: &str, offset: usize, message: S) -> ParseError
+    where
+        S: Into<String>,
+    {
+        let before = &text[..offset];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        let column = before[line_start..].chars().count() + 1;
+
+        ParseError::new(line, column, message)
+    }
+
+    /// Parsers generally work on a line or a piece of a line and report positions relative to
+    /// that. This moves such an error to where the piece sits in the bigger picture. Columns only
+    /// move for errors on the piece's first line, as later lines start from the left edge anyway.
+    pub fn shifted(self, lines: usize, columns: usize) -> ParseError {
+        let column = if self.line == 1 {
+            self.column + columns
+        } else {
+            self.column
+        };
+
+        ParseError {
+            line: self.line + lines,
+            column,
+            ..self
+        }
+    }
+
+    pub fn with_source(self, source_name: &str, text: &str) -> ParseError {
+        let line_text = text
+            .lines()
+            .nth(self.line.saturating_sub(1))
+            .map(str::to_string);
+
+        ParseError {
+            source: Some(SourceContext {
+                name: source_name.to_string(),
+                line_text,
+            }),
+            ..self
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let Some(source) = &self.source else {
+            return write!(
+                f,
+                "line {}, column {}: {}",
+                self.line, self.column, self.message
+            );
+        };
+
+        write!(
+            f,
+            "{}:{}:{}: {}",
+            source.name, self.line, self.column, self.message
+        )?;
+
+        if let Some(line_text) = &source.line_text {
+            let gutter = " ".repeat(self.line.to_string().len());
+            // Keep any tabs so the caret lines up however wide they're shown
+            let indent: String = line_text
+                .chars()
+                .chain(std::iter::repeat(' '))
+                .take(self.column - 1)
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect();
+
+            write!(
+                f,
+                "\n{} |\n{} | {}\n{} | {}^",
+                gutter, self.line, line_text, gutter, indent
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::*;
+
+    #[test]
+    fn at_offset_counts_lines_and_characters() {
+        let text = "abc\nd€f\ng";
+
+        assert_eq!(
+            ParseError::at_offset(text, 0, "x"),
+            ParseError::new(1, 1, "x")
+        );
+        assert_eq!(
+            ParseError::at_offset(text, 4, "x"),
+            ParseError::new(2, 1, "x")
+        );
+        assert_eq!(
+            ParseError::at_offset(text, 8, "x"),
+            ParseError::new(2, 3, "x")
+        );
+        assert_eq!(
+            ParseError::at_offset(text, 10, "x"),
+            ParseError::new(3, 1, "x")
+        );
+    }
+
+    #[test]
+    fn shifted_moves_columns_on_first_line_only() {
+        assert_eq!(
+            ParseError::new(1, 3, "x").shifted(4, 10),
+            ParseError::new(5, 13, "x")
+        );
+        assert_eq!(
+            ParseError::new(2, 3, "x").shifted(4, 10),
+            ParseError::new(6, 3, "x")
+        );
+    }
+
+    #[test]
+    fn display_with_source() {
+        let text = "L68\nL30\nX12\nR48";
+        let error = ParseError::new(3, 1, "expected a turn").with_source("day1.txt", text);
+
+        assert_eq!(
+            error.to_string(),
+            "day1.txt:3:1: expected a turn\n\
+             \x20 |\n\
+             3 | X12\n\
+             \x20 | ^"
+        );
+    }
+
+    #[test]
+    fn display_caret_keeps_tabs_and_can_point_past_the_end() {
+        let text = "\t5-";
+        let error = ParseError::new(1, 4, "missing end").with_source("<stdin>", text);
+
+        assert_eq!(
+            error.to_string(),
+            "<stdin>:1:4: missing end\n  |\n1 | \t5-\n  | \t  ^"
+        );
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            Error::from(ParseError::new(3, 7, "bad")).to_string(),
+            "line 3, column 7: bad"
+        );
+        assert_eq!(
+            Error::UnknownCommand("day99".to_string()).to_string(),
+            "Unknown command: day99"
+        );
+    }
+}
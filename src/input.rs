@@ -0,0 +1,145 @@
+use crate::error::{Error, Result};
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::io::Read;
+use std::path::PathBuf;
+
+pub const INPUTS_DIR_VAR: &str = "AOC_INPUTS_DIR";
+
+const DEFAULT_INPUTS_DIR: &str = "inputs";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Inputs {
+    /// Each day reads `dayN.part1.txt` from this directory
+    Dir(PathBuf),
+    /// Every day reads this one file, which only really makes sense when running a single day
+    File(PathBuf),
+    /// Everything on stdin, asked for with `-` in place of a file name
+    Stdin,
+}
+
+impl Inputs {
+    /// An explicit file wins over an explicit directory, which wins over the environment, which wins
+    /// over `inputs` in the working directory.
+    pub fn resolve(
+        input: Option<PathBuf>,
+        inputs_dir: Option<PathBuf>,
+        env_inputs_dir: Option<OsString>,
+    ) -> Inputs {
+        match (input, inputs_dir, env_inputs_dir) {
+            (Some(file), _, _) if file.as_os_str() == "-" => Inputs::Stdin,
+            (Some(file), _, _) => Inputs::File(file),
+            (None, Some(dir), _) => Inputs::Dir(dir),
+            (None, None, Some(dir)) => Inputs::Dir(PathBuf::from(dir)),
+            (None, None, None) => Inputs::Dir(PathBuf::from(DEFAULT_INPUTS_DIR)),
+        }
+    }
+
+    pub fn path_for(&self, day: u32) -> Option<PathBuf> {
+        match self {
+            Inputs::Dir(dir) => Some(dir.join(format!("day{}.part1.txt", day))),
+            Inputs::File(file) => Some(file.clone()),
+            Inputs::Stdin => None,
+        }
+    }
+
+    /// Known answers live alongside the inputs they belong to
+    pub fn answers_path(&self) -> Option<PathBuf> {
+        match self {
+            Inputs::Dir(dir) => Some(dir.join("answers.toml")),
+            Inputs::File(_) | Inputs::Stdin => None,
+        }
+    }
+
+    /// How to refer to the input for a day in error messages
+    pub fn source_name(&self, day: u32) -> String {
+        match self.path_for(day) {
+            Some(path) => path.display().to_string(),
+            None => "<stdin>".to_string(),
+        }
+    }
+
+    pub fn read(&self, day: u32) -> Result<String> {
+        let mut input = String::new();
+
+        match self.path_for(day) {
+            Some(path) => fs::File::open(path).and_then(|mut f| f.read_to_string(&mut input)),
+            None => io::stdin().read_to_string(&mut input),
+        }
+        .map_err(|error| Error::Io {
+            source_name: self.source_name(day),
+            error,
+        })?;
+
+        Ok(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::input::*;
+
+    #[test]
+    fn resolve_precedence() {
+        let file = || Some(PathBuf::from("some/file.txt"));
+        let dir = || Some(PathBuf::from("flag_dir"));
+        let env = || Some(OsString::from("env_dir"));
+
+        assert_eq!(
+            Inputs::resolve(file(), dir(), env()),
+            Inputs::File(PathBuf::from("some/file.txt"))
+        );
+        assert_eq!(
+            Inputs::resolve(None, dir(), env()),
+            Inputs::Dir(PathBuf::from("flag_dir"))
+        );
+        assert_eq!(
+            Inputs::resolve(None, None, env()),
+            Inputs::Dir(PathBuf::from("env_dir"))
+        );
+        assert_eq!(
+            Inputs::resolve(None, None, None),
+            Inputs::Dir(PathBuf::from("inputs"))
+        );
+        assert_eq!(
+            Inputs::resolve(Some(PathBuf::from("-")), dir(), env()),
+            Inputs::Stdin
+        );
+    }
+
+    #[test]
+    fn path_for_day() {
+        assert_eq!(
+            Inputs::Dir(PathBuf::from("inputs")).path_for(4),
+            Some(PathBuf::from("inputs/day4.part1.txt"))
+        );
+        assert_eq!(
+            Inputs::File(PathBuf::from("x.txt")).path_for(4),
+            Some(PathBuf::from("x.txt"))
+        );
+        assert_eq!(Inputs::Stdin.path_for(4), None);
+    }
+
+    #[test]
+    fn read_reports_path() {
+        let error = Inputs::Dir(PathBuf::from("does/not/exist"))
+            .read(1)
+            .unwrap_err();
+
+        assert!(
+            error
+                .to_string()
+                .starts_with("does/not/exist/day1.part1.txt: ")
+        );
+    }
+
+    #[test]
+    fn source_names() {
+        assert_eq!(
+            Inputs::Dir(PathBuf::from("inputs")).source_name(2),
+            "inputs/day2.part1.txt"
+        );
+        assert_eq!(Inputs::Stdin.source_name(2), "<stdin>");
+    }
+}
@@ -0,0 +1,17 @@
+//! Advent of Code 2025 solutions. Each `dayN` module has the puzzle's `part1` and `part2` plus
+//! whatever they're built from, and a `DayN` [solution::Puzzle] the CLI finds through
+//! [solution::SOLUTIONS].
+
+pub mod bench;
+pub mod cross_check;
+pub mod day1;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod error;
+pub mod input;
+pub mod runner;
+pub mod solution;
+pub mod synthetic;
+pub mod verify;
@@ -1,71 +1,642 @@
-mod day1;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-
+use aoc2025::bench::{self, BenchConfig};
+use aoc2025::cross_check;
+use aoc2025::day1::{self, Clicks, Dial, StartCounts, TraceFormat, Turn};
+use aoc2025::day2::{self, Radix};
+use aoc2025::error::{Error, Result};
+use aoc2025::input::{self, Inputs};
+use aoc2025::runner::{self, PartResult};
+use aoc2025::solution::{self, SOLUTIONS, Solution};
+use aoc2025::synthetic::Rng;
+use aoc2025::verify::{self, Answers};
+use itertools::Itertools;
 use std::env;
-use std::error::Error;
-use std::fmt;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
 
-#[derive(Debug, Clone)]
-struct CliError {
-    details: String,
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Args {
+    command: Option<String>,
+    positional: Vec<String>,
+    input: Option<PathBuf>,
+    inputs_dir: Option<PathBuf>,
+    answers: Option<PathBuf>,
+    part: Option<u32>,
+    bench: BenchConfig,
+    trace_format: TraceFormat,
+    dial_size: Option<i64>,
+    target: Option<Clicks>,
+    cross_check: bool,
+    radix: Option<u32>,
+    unique: bool,
+    explain: bool,
 }
 
-impl CliError {
-    fn new(msg: &str) -> CliError {
-        CliError {
-            details: msg.to_string(),
+fn parse_args<I>(args: I) -> Result<Args>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut parsed = Args::default();
+    let mut args = args.into_iter();
+    let mut flags = vec![];
+
+    while let Some(arg) = args.next() {
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value.into())),
+            _ => (arg, None),
+        };
+
+        let value = || {
+            inline_value
+                .or_else(|| args.next())
+                .ok_or_else(|| Error::Usage(format!("{} needs a value", flag)))
+        };
+
+        if flag.starts_with("--") {
+            flags.push(flag.clone());
+        }
+
+        match flag.as_str() {
+            "--input" => parsed.input = Some(value()?.into()),
+            "--inputs-dir" => parsed.inputs_dir = Some(value()?.into()),
+            "--answers" => parsed.answers = Some(value()?.into()),
+            "--part" => parsed.part = Some(parse_number(&flag, value()?)?),
+            "--runs" => parsed.bench.runs = parse_number(&flag, value()?)?,
+            "--warmup" => parsed.bench.warmup = parse_number(&flag, value()?)?,
+            "--format" => parsed.trace_format = value()?.parse()?,
+            "--size" => parsed.dial_size = Some(parse_number(&flag, value()?)?),
+            "--target" => parsed.target = Some(parse_number(&flag, value()?)?),
+            "--cross-check" => parsed.cross_check = true,
+            "--unique" => parsed.unique = true,
+            "--explain" => parsed.explain = true,
+            "--radix" => parsed.radix = Some(parse_number(&flag, value()?)?),
+            unknown if unknown.starts_with("--") => {
+                return Err(Error::Usage(format!("Unknown flag: {}", unknown)));
+            }
+            _ if parsed.command.is_none() => parsed.command = Some(flag),
+            _ => parsed.positional.push(flag),
         }
     }
 
-    fn from_string(msg: String) -> CliError {
-        CliError { details: msg }
+    // A flag the command doesn't look at is a mistake, better to say so than quietly ignore it
+    if let Some(command) = &parsed.command {
+        let command = match command.as_str() {
+            "list" | "verify" | "bench" | "trace" | "starts" | "ids" => command.as_str(),
+            _ => RUNNING_DAYS,
+        };
+
+        for flag in flags {
+            if let Some(commands) = commands_taking(&flag)
+                && !commands.contains(&command)
+            {
+                return Err(Error::Usage(format!(
+                    "{} only works with {}",
+                    flag,
+                    commands.join(" or ")
+                )));
+            }
+        }
     }
+
+    Ok(parsed)
+}
+
+// Any command that isn't one of the named ones picks days to run
+const RUNNING_DAYS: &str = "running days";
+
+/// The commands a flag works with, or nothing for flags every command takes
+fn commands_taking(flag: &str) -> Option<&'static [&'static str]> {
+    match flag {
+        "--answers" => Some(&["verify"]),
+        "--part" => Some(&["bench", "starts", "ids"]),
+        "--runs" | "--warmup" => Some(&["bench"]),
+        "--format" => Some(&["trace"]),
+        "--size" | "--target" => Some(&["starts"]),
+        // Only ids knows about other radixes, the days themselves are the decimal puzzle
+        "--radix" | "--unique" | "--explain" => Some(&["ids"]),
+        "--cross-check" => Some(&[RUNNING_DAYS]),
+        _ => None,
+    }
+}
+
+fn parse_number<T: FromStr>(flag: &str, value: String) -> Result<T> {
+    value
+        .parse()
+        .map_err(|_| Error::Usage(format!("{} needs a number, not {:?}", flag, value)))
 }
 
-impl fmt::Display for CliError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.details)
+fn main() -> ExitCode {
+    match parse_args(env::args().skip(1)).and_then(dispatch) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::FAILURE
+        }
     }
 }
 
-impl Error for CliError {}
+fn dispatch(mut args: Args) -> Result<ExitCode> {
+    let Some(command) = args.command.take() else {
+        return Err(Error::Usage("No command specified".to_string()));
+    };
+
+    match command.as_str() {
+        "list" => {
+            for solution in SOLUTIONS {
+                println!("{}", solution.name());
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        "verify" => {
+            let spec = single_positional(&mut args)?.unwrap_or_else(|| "all".to_string());
+            let solutions = select(&spec)?;
+            let inputs = resolve_inputs(args.input, args.inputs_dir, &solutions)?;
+            let answers_path = args
+                .answers
+                .or_else(|| inputs.answers_path())
+                .ok_or_else(|| {
+                    Error::Usage(
+                        "--answers is needed when not reading from an inputs directory".to_string(),
+                    )
+                })?;
+
+            verify(&solutions, &inputs, &Answers::load(answers_path)?)
+        }
+        "bench" => {
+            let spec = single_positional(&mut args)?
+                .ok_or_else(|| Error::Usage("bench needs a day to run".to_string()))?;
+            let solutions = select(&spec)?;
+            let inputs = resolve_inputs(args.input, args.inputs_dir, &solutions)?;
+            let parts = match args.part {
+                Some(part) => vec![part],
+                None => vec![1, 2],
+            };
+
+            bench(&solutions, &inputs, &parts, &args.bench)
+        }
+        "trace" => {
+            let input = positional_input(&mut args)?;
+            let inputs =
+                Inputs::resolve(input, args.inputs_dir, env::var_os(input::INPUTS_DIR_VAR));
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let args: Vec<String> = env::args().collect();
+            trace(&inputs, args.trace_format)
+        }
+        "starts" => {
+            let input = positional_input(&mut args)?;
+            let inputs =
+                Inputs::resolve(input, args.inputs_dir, env::var_os(input::INPUTS_DIR_VAR));
 
-    if args.len() < 2 {
-        return Err(CliError::new("No command specified").into());
+            starts(
+                &inputs,
+                args.dial_size.unwrap_or(100),
+                args.part,
+                args.target,
+            )
+        }
+        "ids" => {
+            let input = positional_input(&mut args)?;
+            let inputs =
+                Inputs::resolve(input, args.inputs_dir, env::var_os(input::INPUTS_DIR_VAR));
+
+            ids(
+                &inputs,
+                args.radix.unwrap_or(10),
+                args.unique,
+                args.explain,
+                args.part,
+            )
+        }
+        spec => {
+            let solutions = select(spec)?;
+            let input = positional_input(&mut args)?;
+            let inputs = resolve_inputs(input, args.inputs_dir, &solutions)?;
+
+            let texts = runner::read_all(&solutions, &inputs);
+            let code = run(&solutions, &inputs, &texts)?;
+
+            if args.cross_check && cross_check(&solutions, &inputs, &texts)? != ExitCode::SUCCESS {
+                Ok(ExitCode::FAILURE)
+            } else {
+                Ok(code)
+            }
+        }
     }
+}
 
-    match args[1].as_str() {
-        "day1" => {
-            println!("Day 1 part 1: {}", day1::part1()?);
-            println!("Day 1 part 2: {}", day1::part2()?);
-            Ok(())
+// A positional argument after the command is shorthand for --input, mostly so `day5 -` works
+fn positional_input(args: &mut Args) -> Result<Option<PathBuf>> {
+    match (args.input.take(), single_positional(args)?) {
+        (Some(_), Some(_)) => Err(Error::Usage(
+            "Input given both positionally and with --input".to_string(),
+        )),
+        (flag, positional) => Ok(flag.or(positional.map(PathBuf::from))),
+    }
+}
+
+fn single_positional(args: &mut Args) -> Result<Option<String>> {
+    if args.positional.len() > 1 {
+        return Err(Error::Usage(format!(
+            "Unexpected argument: {}",
+            args.positional[1]
+        )));
+    }
+
+    Ok(args.positional.pop())
+}
+
+fn select(spec: &str) -> Result<Vec<&'static dyn Solution>> {
+    solution::select(spec).ok_or_else(|| Error::UnknownCommand(spec.to_string()))
+}
+
+fn resolve_inputs(
+    input: Option<PathBuf>,
+    inputs_dir: Option<PathBuf>,
+    solutions: &[&dyn Solution],
+) -> Result<Inputs> {
+    if input.is_some() && solutions.len() > 1 {
+        return Err(Error::Usage(
+            "--input can only be used with a single day".to_string(),
+        ));
+    }
+
+    Ok(Inputs::resolve(
+        input,
+        inputs_dir,
+        env::var_os(input::INPUTS_DIR_VAR),
+    ))
+}
+
+fn run(solutions: &[&dyn Solution], inputs: &Inputs, texts: &[Result<String>]) -> Result<ExitCode> {
+    let results = runner::run_read(solutions, inputs, texts);
+
+    println!("{}", runner::format_table(&results));
+    print_diagnostics(&results);
+
+    let failures = results.iter().filter(|r| r.failed()).count();
+    Ok(exit_code(failures, "failed"))
+}
+
+fn cross_check(
+    solutions: &[&dyn Solution],
+    inputs: &Inputs,
+    texts: &[Result<String>],
+) -> Result<ExitCode> {
+    // Different every run so repeated runs cover more ground, a divergence prints its input anyway
+    let seed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos() as u64);
+    let mut rng = Rng::new(seed);
+    let mut failures = 0;
+
+    println!();
+    for (solution, text) in solutions.iter().zip(texts) {
+        let day = solution.day();
+        let Ok(input) = text else {
+            // The error's already in the table, but the real input not being checked shouldn't pass
+            eprintln!("{}: can't cross-check without its input", solution.name());
+            failures += 1;
+            continue;
+        };
+
+        let reports = cross_check::run_all(&[(day, input)], cross_check::RANDOM_INPUTS, &mut rng)
+            .map_err(|e| e.with_source(&inputs.source_name(day), input))?;
+
+        for report in reports {
+            println!("{}", report);
+            if !report.passed() {
+                failures += 1;
+            }
         }
-        "day2" => {
-            println!("Day 2 part 1: {}", day2::part1()?);
-            println!("Day 2 part 2: {}", day2::part2()?);
-            Ok(())
+    }
+
+    if failures > 0 {
+        eprintln!("{} cross-check(s) failed", failures);
+        Ok(ExitCode::FAILURE)
+    } else {
+        Ok(ExitCode::SUCCESS)
+    }
+}
+
+fn verify(solutions: &[&dyn Solution], inputs: &Inputs, answers: &Answers) -> Result<ExitCode> {
+    let results = runner::run_all(solutions, inputs);
+    let verifications = verify::verify(&results, answers);
+
+    println!("{}", verify::format_report(&verifications));
+    print_diagnostics(&results);
+
+    let failures = verifications.iter().filter(|v| v.failed()).count();
+    Ok(exit_code(failures, "failed verification"))
+}
+
+fn bench(
+    solutions: &[&dyn Solution],
+    inputs: &Inputs,
+    parts: &[u32],
+    config: &BenchConfig,
+) -> Result<ExitCode> {
+    let mut reports = vec![];
+
+    for solution in solutions {
+        let input = inputs.read(solution.day())?;
+
+        for part in parts {
+            let report = solution
+                .bench(*part, &input, config)
+                .map_err(|e| e.with_source(&inputs.source_name(solution.day()), &input))?;
+            reports.push(report);
         }
-        "day3" => {
-            println!("Day 3 part 1: {}", day3::part1()?);
-            println!("Day 3 part 2: {}", day3::part2()?);
-            Ok(())
+    }
+
+    println!("{}", bench::format_reports(&reports));
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn trace(inputs: &Inputs, format: TraceFormat) -> Result<ExitCode> {
+    let turns = read_turns(inputs)?;
+
+    println!(
+        "{}",
+        day1::format_trace(&day1::trace(Dial::new(), &turns), format)
+    );
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn starts(
+    inputs: &Inputs,
+    size: i64,
+    part: Option<u32>,
+    target: Option<Clicks>,
+) -> Result<ExitCode> {
+    if !(1..=StartCounts::MAX_SIZE).contains(&size) {
+        return Err(Error::Usage(format!(
+            "--size must be between 1 and {}",
+            StartCounts::MAX_SIZE
+        )));
+    }
+    let parts = match part {
+        Some(part @ (1 | 2)) => vec![part],
+        Some(part) => return Err(Error::Usage(format!("Day 1 has no part {}", part))),
+        None => vec![1, 2],
+    };
+
+    let counts = StartCounts::of(size, &read_turns(inputs)?);
+
+    println!("{}", day1::format_histogram(&counts, &parts));
+
+    if let Some(target) = target {
+        for part in parts {
+            let starts = day1::starts_giving(counts.for_part(part).unwrap_or_default(), target);
+            let starts = if starts.is_empty() {
+                "nowhere".to_string()
+            } else {
+                starts.iter().map(|s| s.to_string()).join(", ")
+            };
+
+            println!("\nPart {} gives {} starting from {}", part, target, starts);
         }
-        "day4" => {
-            println!("Day 4 part 1: {}", day4::part1()?);
-            println!("Day 4 part 2: {}", day4::part2()?);
-            Ok(())
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn ids(
+    inputs: &Inputs,
+    radix: u32,
+    unique: bool,
+    explain: bool,
+    part: Option<u32>,
+) -> Result<ExitCode> {
+    let radix = Radix::new(radix)
+        .ok_or_else(|| Error::Usage("--radix must be between 2 and 36".to_string()))?;
+    let parts = match part {
+        Some(part @ (1 | 2)) => vec![part],
+        Some(part) => return Err(Error::Usage(format!("Day 2 has no part {}", part))),
+        None => vec![1, 2],
+    };
+    let input = inputs.read(2)?;
+    let ranges = day2::parse_ranges(&input, radix)
+        .map_err(|e| Error::from(e).with_source(&inputs.source_name(2), &input))?;
+
+    let totalled = if unique {
+        day2::normalise_ranges(&ranges)
+    } else {
+        ranges.clone()
+    };
+
+    if explain {
+        // Part 2's invalid IDs include part 1's, so that's the one to explain unless told otherwise
+        let policy = day2::part_policy(*parts.last().unwrap()).unwrap();
+
+        println!("{}\n", day2::format_explanations(&totalled, &policy, radix));
+    }
+
+    println!("{}", day2::format_totals(&totalled, &parts, radix)?);
+
+    let overlaps = day2::format_overlaps(&ranges, radix);
+    if !overlaps.is_empty() {
+        println!("\n{}", overlaps);
+        if !unique {
+            println!(
+                "IDs in more than one range were counted for each of them, --unique counts them once"
+            );
         }
-        "day5" => {
-            println!("Day 5 part 1: {}", day5::part1()?);
-            println!("Day 5 part 2: {}", day5::part2()?);
-            Ok(())
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn read_turns(inputs: &Inputs) -> Result<Vec<Turn>> {
+    let input = inputs.read(1)?;
+
+    day1::parse_turns(&input)
+        .map_err(|e| Error::from(e).with_source(&inputs.source_name(1), &input))
+}
+
+fn print_diagnostics(results: &[PartResult]) {
+    for diagnostic in runner::diagnostics(results) {
+        eprintln!("\n{}", diagnostic);
+    }
+}
+
+fn exit_code(failures: usize, what: &str) -> ExitCode {
+    if failures > 0 {
+        eprintln!("{} part(s) {}", failures, what);
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    fn parse(args: &[&str]) -> Result<Args> {
+        parse_args(args.iter().map(|a| a.to_string()))
+    }
+
+    #[test]
+    fn parse_args_flags() {
+        assert_eq!(
+            parse(&["day1", "--input", "x.txt"]).unwrap(),
+            Args {
+                command: Some("day1".to_string()),
+                input: Some(PathBuf::from("x.txt")),
+                ..Args::default()
+            }
+        );
+        assert_eq!(
+            parse(&[
+                "--inputs-dir=elsewhere",
+                "verify",
+                "--answers",
+                "a.toml",
+                "1-3"
+            ])
+            .unwrap(),
+            Args {
+                command: Some("verify".to_string()),
+                positional: vec!["1-3".to_string()],
+                inputs_dir: Some(PathBuf::from("elsewhere")),
+                answers: Some(PathBuf::from("a.toml")),
+                ..Args::default()
+            }
+        );
+        assert_eq!(
+            parse(&["day5", "-"]).unwrap(),
+            Args {
+                command: Some("day5".to_string()),
+                positional: vec!["-".to_string()],
+                ..Args::default()
+            }
+        );
+        assert_eq!(
+            parse(&["day2", "--cross-check"]).unwrap(),
+            Args {
+                command: Some("day2".to_string()),
+                cross_check: true,
+                ..Args::default()
+            }
+        );
+    }
+
+    #[test]
+    fn parse_args_bench() {
+        assert_eq!(
+            parse(&["bench", "day2", "--part=2", "--runs", "50", "--warmup", "0"]).unwrap(),
+            Args {
+                command: Some("bench".to_string()),
+                positional: vec!["day2".to_string()],
+                part: Some(2),
+                bench: BenchConfig {
+                    warmup: 0,
+                    runs: 50
+                },
+                ..Args::default()
+            }
+        );
+        assert!(parse(&["bench", "day2", "--runs", "lots"]).is_err());
+    }
+
+    #[test]
+    fn parse_args_trace() {
+        assert_eq!(
+            parse(&["trace", "--format", "csv", "-"]).unwrap(),
+            Args {
+                command: Some("trace".to_string()),
+                positional: vec!["-".to_string()],
+                trace_format: TraceFormat::Csv,
+                ..Args::default()
+            }
+        );
+        assert!(parse(&["trace", "--format=json"]).is_err());
+    }
+
+    #[test]
+    fn parse_args_starts() {
+        assert_eq!(
+            parse(&["starts", "--size", "60", "--target=3", "--part", "1"]).unwrap(),
+            Args {
+                command: Some("starts".to_string()),
+                part: Some(1),
+                dial_size: Some(60),
+                target: Some(3),
+                ..Args::default()
+            }
+        );
+        assert!(parse(&["starts", "--target", "three"]).is_err());
+    }
+
+    #[test]
+    fn parse_args_ids() {
+        assert_eq!(
+            parse(&["ids", "--radix", "16", "ranges.txt"]).unwrap(),
+            Args {
+                command: Some("ids".to_string()),
+                positional: vec!["ranges.txt".to_string()],
+                radix: Some(16),
+                ..Args::default()
+            }
+        );
+        assert!(parse(&["ids", "--radix=hex"]).is_err());
+        assert!(matches!(
+            parse(&["day2", "--radix", "16"]).and_then(dispatch),
+            Err(Error::Usage(_))
+        ));
+        assert!(parse(&["ids", "--unique"]).unwrap().unique);
+        assert_eq!(
+            parse(&["ids", "--explain", "--part", "1"]).unwrap(),
+            Args {
+                command: Some("ids".to_string()),
+                part: Some(1),
+                explain: true,
+                ..Args::default()
+            }
+        );
+    }
+
+    #[test]
+    fn parse_args_errors() {
+        assert!(parse(&["day1", "--input"]).is_err());
+        assert!(parse(&["day1", "--bananas"]).is_err());
+    }
+
+    #[test]
+    fn parse_args_rejects_flags_for_other_commands() {
+        for args in [
+            &["day2", "--unique"][..],
+            &["day2", "--explain", "--format", "csv"],
+            &["starts", "--radix=16"],
+            &["day1", "--size", "60"],
+            &["trace", "--target", "3"],
+            &["verify", "--cross-check"],
+            &["bench", "day1", "--cross-check"],
+            &["--answers", "a.toml", "day1"],
+            &["day1", "--part", "1"],
+            &["ids", "--runs", "5"],
+            &["list", "--format", "csv"],
+        ] {
+            assert!(
+                matches!(parse(args), Err(Error::Usage(_))),
+                "{:?} should be rejected",
+                args
+            );
         }
-        cmd => Err(CliError::from_string(format!("Unknown command: {}", cmd)).into()),
+
+        assert_eq!(
+            parse(&["verify", "--part", "2"]).unwrap_err().to_string(),
+            Error::Usage("--part only works with bench or starts or ids".to_string()).to_string()
+        );
+        assert!(parse(&["1-3", "--cross-check", "--inputs-dir", "elsewhere"]).is_ok());
+    }
+
+    #[test]
+    fn single_positional_rejects_extras() {
+        let mut args = parse(&["day1", "-", "day2"]).unwrap();
+
+        assert!(single_positional(&mut args).is_err());
     }
 }
@@ -0,0 +1,196 @@
+use crate::error::{Error, Result};
+use crate::input::Inputs;
+use crate::solution::{Answer, Solution};
+use itertools::Itertools;
+use std::time::{Duration, Instant};
+
+#[derive(Debug)]
+pub struct PartResult {
+    pub day: u32,
+    pub part: u32,
+    pub answer: Result<String, String>,
+    pub elapsed: Duration,
+}
+
+impl PartResult {
+    pub fn failed(&self) -> bool {
+        self.answer.is_err()
+    }
+
+    /// Errors can run over several lines (see [Error::with_source]), only the first is a summary
+    pub fn summary(&self) -> String {
+        match &self.answer {
+            Ok(answer) => answer.clone(),
+            Err(e) => format!("ERROR: {}", e.lines().next().unwrap_or_default()),
+        }
+    }
+}
+
+// Errors are captured rather than propagated so one broken day doesn't hide the rest
+pub fn run_all(solutions: &[&dyn Solution], inputs: &Inputs) -> Vec<PartResult> {
+    run_read(solutions, inputs, &read_all(solutions, inputs))
+}
+
+/// Each day's input, read up front for anything that needs it more than once, as stdin can only be
+/// read the once
+pub fn read_all(solutions: &[&dyn Solution], inputs: &Inputs) -> Vec<Result<String>> {
+    solutions.iter().map(|s| inputs.read(s.day())).collect()
+}
+
+/// [run_all] with the inputs from [read_all], in the same order as the solutions
+pub fn run_read(
+    solutions: &[&dyn Solution],
+    inputs: &Inputs,
+    texts: &[Result<String>],
+) -> Vec<PartResult> {
+    solutions
+        .iter()
+        .zip(texts)
+        .flat_map(|(s, text)| match text {
+            Ok(input) => {
+                let source_name = inputs.source_name(s.day());
+                let in_context = |e: Error| e.with_source(&source_name, input);
+
+                [
+                    run_part(s.day(), 1, || s.part1(input).map_err(in_context)),
+                    run_part(s.day(), 2, || s.part2(input).map_err(in_context)),
+                ]
+            }
+            Err(e) => [1, 2].map(|part| PartResult {
+                day: s.day(),
+                part,
+                answer: Err(e.to_string()),
+                elapsed: Duration::ZERO,
+            }),
+        })
+        .collect()
+}
+
+fn run_part<F>(day: u32, part: u32, f: F) -> PartResult
+where
+    F: FnOnce() -> Result<Answer>,
+{
+    let start = Instant::now();
+    let answer = f().map(|a| a.to_string()).map_err(|e| e.to_string());
+    let elapsed = start.elapsed();
+
+    PartResult {
+        day,
+        part,
+        answer,
+        elapsed,
+    }
+}
+
+/// The full text of errors too long for a table cell, each only once as both parts of a day tend
+/// to fail the same way
+pub fn diagnostics(results: &[PartResult]) -> Vec<&str> {
+    results
+        .iter()
+        .filter_map(|r| r.answer.as_ref().err())
+        .filter(|e| e.contains('\n'))
+        .map(|e| e.as_str())
+        .unique()
+        .collect()
+}
+
+pub fn format_table(results: &[PartResult]) -> String {
+    let rows: Vec<[String; 4]> = results
+        .iter()
+        .map(|r| {
+            [
+                r.day.to_string(),
+                r.part.to_string(),
+                r.summary(),
+                format!("{:.2?}", r.elapsed),
+            ]
+        })
+        .collect();
+
+    format_rows(["Day", "Part", "Answer", "Time"], &rows)
+}
+
+pub fn format_rows<const N: usize>(header: [&str; N], rows: &[[String; N]]) -> String {
+    let mut widths = header.map(|h| h.len());
+    for row in rows {
+        for (i, cell) in row.iter().enumerate() {
+            widths[i] = widths[i].max(cell.chars().count());
+        }
+    }
+
+    let format_row = |cells: [&str; N]| {
+        cells
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join(" | ")
+            .trim_end()
+            .to_string()
+    };
+
+    let mut lines = vec![
+        format_row(header),
+        widths
+            .iter()
+            .map(|w| "-".repeat(*w))
+            .collect::<Vec<_>>()
+            .join("-+-"),
+    ];
+    lines.extend(
+        rows.iter()
+            .map(|row| format_row(row.each_ref().map(|c| c.as_str()))),
+    );
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::runner::*;
+
+    #[test]
+    fn format_table_pads_columns() {
+        let results = vec![
+            PartResult {
+                day: 1,
+                part: 1,
+                answer: Ok("1234".to_string()),
+                elapsed: Duration::from_millis(3),
+            },
+            PartResult {
+                day: 1,
+                part: 2,
+                answer: Err("oh no".to_string()),
+                elapsed: Duration::from_micros(10),
+            },
+        ];
+
+        assert_eq!(
+            format_table(&results),
+            "Day | Part | Answer       | Time\n\
+             ----+------+--------------+--------\n\
+             1   | 1    | 1234         | 3.00ms\n\
+             1   | 2    | ERROR: oh no | 10.00µs"
+        );
+    }
+
+    #[test]
+    fn diagnostics_are_deduplicated() {
+        let result = |part, answer| PartResult {
+            day: 4,
+            part,
+            answer,
+            elapsed: Duration::ZERO,
+        };
+        let diagnostic = "x.txt:1:2: bad\n  |\n1 | .x\n  |  ^".to_string();
+        let results = vec![
+            result(1, Err(diagnostic.clone())),
+            result(2, Err(diagnostic.clone())),
+            result(3, Err("short".to_string())),
+        ];
+
+        assert_eq!(results[0].summary(), "ERROR: x.txt:1:2: bad");
+        assert_eq!(diagnostics(&results), vec![diagnostic.as_str()]);
+    }
+}
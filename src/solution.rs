@@ -0,0 +1,131 @@
+use crate::bench::{self, BenchConfig, BenchReport};
+use crate::error::Result;
+use crate::{day1, day2, day3, day4, day5};
+use std::fmt::Display;
+
+pub type Answer = Box<dyn Display>;
+
+/// What each day implements. Parsing is kept apart from solving so the two can be timed separately.
+pub trait Puzzle: Sync {
+    const DAY: u32;
+
+    type Input;
+
+    fn parse(input: &str) -> Result<Self::Input>;
+
+    fn part1(input: &Self::Input) -> Result<Answer>;
+
+    fn part2(input: &Self::Input) -> Result<Answer>;
+}
+
+/// A [Puzzle] with the types erased so different days can sit together in [SOLUTIONS]
+pub trait Solution: Sync {
+    fn day(&self) -> u32;
+
+    fn name(&self) -> String {
+        format!("day{}", self.day())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer>;
+
+    fn part2(&self, input: &str) -> Result<Answer>;
+
+    fn bench(&self, part: u32, input: &str, config: &BenchConfig) -> Result<BenchReport>;
+}
+
+impl<P: Puzzle> Solution for P {
+    fn day(&self) -> u32 {
+        P::DAY
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer> {
+        <P as Puzzle>::part1(&P::parse(input)?)
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer> {
+        <P as Puzzle>::part2(&P::parse(input)?)
+    }
+
+    fn bench(&self, part: u32, input: &str, config: &BenchConfig) -> Result<BenchReport> {
+        bench::bench::<P>(part, input, config)
+    }
+}
+
+// Adding a day means adding it here and nowhere else
+pub static SOLUTIONS: &[&dyn Solution] = &[
+    &day1::Day1,
+    &day2::Day2,
+    &day3::Day3,
+    &day4::Day4,
+    &day5::Day5,
+];
+
+pub fn find(name: &str) -> Option<&'static dyn Solution> {
+    SOLUTIONS.iter().copied().find(|s| s.name() == name)
+}
+
+/// Resolves a day selection: `all`, a single day by name (`day3`) or number (`3`), or an inclusive
+/// range of day numbers (`1-3`).
+pub fn select(spec: &str) -> Option<Vec<&'static dyn Solution>> {
+    if spec == "all" {
+        return Some(SOLUTIONS.to_vec());
+    }
+
+    if let Some(solution) = find(spec) {
+        return Some(vec![solution]);
+    }
+
+    let (first, last) = match spec.split_once('-') {
+        Some((first, last)) => (first.parse::<u32>().ok()?, last.parse::<u32>().ok()?),
+        None => {
+            let day = spec.parse::<u32>().ok()?;
+            (day, day)
+        }
+    };
+
+    let selected: Vec<_> = (first..=last)
+        .map(|day| SOLUTIONS.iter().copied().find(|s| s.day() == day))
+        .collect::<Option<_>>()?;
+
+    if selected.is_empty() {
+        None
+    } else {
+        Some(selected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::solution::*;
+
+    #[test]
+    fn solutions_are_in_day_order() {
+        let days: Vec<u32> = SOLUTIONS.iter().map(|s| s.day()).collect();
+
+        assert_eq!(days, (1..=SOLUTIONS.len() as u32).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn find_by_name() {
+        assert_eq!(find("day3").map(|s| s.day()), Some(3));
+        assert!(find("day0").is_none());
+        assert!(find("3").is_none());
+    }
+
+    #[test]
+    fn select_specs() {
+        fn days(spec: &str) -> Option<Vec<u32>> {
+            select(spec).map(|s| s.iter().map(|s| s.day()).collect())
+        }
+
+        assert_eq!(days("all"), Some(vec![1, 2, 3, 4, 5]));
+        assert_eq!(days("day2"), Some(vec![2]));
+        assert_eq!(days("4"), Some(vec![4]));
+        assert_eq!(days("1-3"), Some(vec![1, 2, 3]));
+        assert_eq!(days("3-3"), Some(vec![3]));
+        assert_eq!(days("3-1"), None);
+        assert_eq!(days("0-2"), None);
+        assert_eq!(days("4-99"), None);
+        assert_eq!(days("bananas"), None);
+    }
+}
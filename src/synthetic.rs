@@ -0,0 +1,125 @@
+//! Made-up puzzle inputs, for benchmarks and tests that want something bigger or more varied than
+//! the examples. Everything is seeded so the same seed always gives the same input.
+
+use crate::day1::Turn;
+use crate::day2::{Id, IdRange};
+use std::ops::RangeInclusive;
+
+/// xorshift64*, which is plenty random for making up inputs and saves a dependency
+#[derive(Clone, Debug)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        // Zero is a fixed point of xorshift so nudge it off
+        Rng(seed ^ 0x9E37_79B9_7F4A_7C15)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// Slightly biased for huge ranges, which doesn't matter here
+    pub fn in_range(&mut self, range: RangeInclusive<u64>) -> u64 {
+        let width = range.end() - range.start();
+        if width == u64::MAX {
+            self.next_u64()
+        } else {
+            range.start() + self.next_u64() % (width + 1)
+        }
+    }
+
+    pub fn chance(&mut self, percent: u64) -> bool {
+        self.in_range(0..=99) < percent
+    }
+}
+
+pub fn turns(rng: &mut Rng, count: usize, max_amount: u64) -> Vec<Turn> {
+    (0..count)
+        .map(|_| {
+            let amount = rng.in_range(1..=max_amount) as i64;
+            Turn(if rng.chance(50) { amount } else { -amount })
+        })
+        .collect()
+}
+
+/// A range starting at a random ID with `digits` digits and covering `width` IDs
+pub fn id_range(rng: &mut Rng, digits: u32, width: u64) -> IdRange {
+    let start = Id::from(rng.in_range(10u64.pow(digits - 1)..=10u64.pow(digits) - 1));
+
+    start..=start.saturating_add(Id::from(width) - 1)
+}
+
+pub fn bank(rng: &mut Rng, length: usize) -> Vec<u32> {
+    (0..length).map(|_| rng.in_range(1..=9) as u32).collect()
+}
+
+/// Text for a day 4 grid where `percent` of the cells hold rolls
+pub fn grid(rng: &mut Rng, rows: usize, columns: usize, percent: u64) -> String {
+    (0..rows)
+        .map(|_| {
+            (0..columns)
+                .map(|_| if rng.chance(percent) { '@' } else { '.' })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn ranges(rng: &mut Rng, count: usize, max: u64, max_width: u64) -> Vec<RangeInclusive<u64>> {
+    (0..count)
+        .map(|_| {
+            let start = rng.in_range(0..=max);
+            start..=start.saturating_add(rng.in_range(0..=max_width))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::synthetic::*;
+
+    #[test]
+    fn same_seed_same_numbers() {
+        let mut a = Rng::new(0);
+        let mut b = Rng::new(0);
+        let mut c = Rng::new(1);
+
+        let from_a: Vec<u64> = (0..10).map(|_| a.next_u64()).collect();
+        let from_b: Vec<u64> = (0..10).map(|_| b.next_u64()).collect();
+        let from_c: Vec<u64> = (0..10).map(|_| c.next_u64()).collect();
+
+        assert_eq!(from_a, from_b);
+        assert_ne!(from_a, from_c);
+    }
+
+    #[test]
+    fn generated_values_in_bounds() {
+        let mut rng = Rng::new(42);
+
+        assert!((0..1000).all(|_| (5..=7).contains(&rng.in_range(5..=7))));
+        assert!(
+            turns(&mut rng, 100, 250)
+                .iter()
+                .all(|t| t.0 != 0 && t.0.abs() <= 250)
+        );
+
+        let range = id_range(&mut rng, 4, 50);
+        assert!((1000..=9999).contains(range.start()));
+        assert_eq!(range.end() - range.start(), 49);
+
+        assert!(bank(&mut rng, 100).iter().all(|b| (1..=9).contains(b)));
+    }
+
+    #[test]
+    fn grid_parses() {
+        let text = grid(&mut Rng::new(7), 5, 8, 60);
+
+        assert_eq!(text.lines().count(), 5);
+        assert!(text.lines().all(|l| l.len() == 8));
+        assert!(text.parse::<crate::day4::Grid>().is_ok());
+    }
+}
@@ -0,0 +1,243 @@
+use crate::error::{Error, ParseError, Result};
+use crate::runner::{PartResult, format_rows};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use toml::Spanned;
+use toml::de::{DeTable, DeValue};
+
+/// Known answers keyed by day and part, read from a file like:
+///
+/// ```toml
+/// [day1]
+/// part1 = 1234
+/// part2 = "can also be a string"
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Answers(HashMap<(u32, u32), String>);
+
+impl Answers {
+    pub fn load<P>(path: P) -> Result<Answers>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path).map_err(|error| Error::Io {
+            source_name: path.display().to_string(),
+            error,
+        })?;
+
+        Answers::parse(&text)
+            .map_err(|e| Error::from(e).with_source(&path.display().to_string(), &text))
+    }
+
+    pub fn parse(text: &str) -> Result<Answers, ParseError> {
+        let error_at =
+            |offset: usize, message: String| ParseError::at_offset(text, offset, message);
+
+        let table = DeTable::parse(text).map_err(|e| {
+            error_at(
+                e.span().map(|s| s.start).unwrap_or(0),
+                e.message().trim().to_string(),
+            )
+        })?;
+
+        let mut answers = HashMap::new();
+
+        for (day_name, parts) in table.get_ref() {
+            let day = parse_key(day_name, "day").map_err(|m| error_at(day_name.span().start, m))?;
+            let DeValue::Table(parts) = parts.get_ref() else {
+                return Err(error_at(
+                    parts.span().start,
+                    format!("[{}] should be a table of parts", day_name.get_ref()),
+                ));
+            };
+
+            for (part_name, answer) in parts {
+                let part = parse_key(part_name, "part")
+                    .map_err(|m| error_at(part_name.span().start, m))?;
+                let answer = match answer.get_ref() {
+                    DeValue::String(s) => s.to_string(),
+                    DeValue::Integer(i) if i.radix() == 10 => i.as_str().replace('_', ""),
+                    _ => {
+                        return Err(error_at(
+                            answer.span().start,
+                            format!(
+                                "{}.{} should be a string or decimal integer",
+                                day_name.get_ref(),
+                                part_name.get_ref()
+                            ),
+                        ));
+                    }
+                };
+
+                answers.insert((day, part), answer);
+            }
+        }
+
+        Ok(Answers(answers))
+    }
+
+    pub fn get(&self, day: u32, part: u32) -> Option<&str> {
+        self.0.get(&(day, part)).map(|s| s.as_str())
+    }
+}
+
+fn parse_key(key: &Spanned<impl AsRef<str>>, prefix: &str) -> Result<u32, String> {
+    let key = key.get_ref().as_ref();
+
+    key.strip_prefix(prefix)
+        .and_then(|n| n.parse().ok())
+        .ok_or_else(|| format!("expected a key like {}1, found {:?}", prefix, key))
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: String },
+    Missing,
+}
+
+#[derive(Debug)]
+pub struct Verification<'a> {
+    pub result: &'a PartResult,
+    pub verdict: Verdict,
+}
+
+impl Verification<'_> {
+    pub fn failed(&self) -> bool {
+        matches!(self.verdict, Verdict::Fail { .. })
+    }
+}
+
+// A part that errors can never pass, whether or not we know what it should have said
+pub fn verify<'a>(results: &'a [PartResult], answers: &Answers) -> Vec<Verification<'a>> {
+    results
+        .iter()
+        .map(|result| {
+            let verdict = match (answers.get(result.day, result.part), &result.answer) {
+                (Some(expected), Ok(actual)) if expected == actual => Verdict::Pass,
+                (Some(expected), _) => Verdict::Fail {
+                    expected: expected.to_string(),
+                },
+                (None, Ok(_)) => Verdict::Missing,
+                (None, Err(_)) => Verdict::Fail {
+                    expected: "?".to_string(),
+                },
+            };
+
+            Verification { result, verdict }
+        })
+        .collect()
+}
+
+pub fn format_report(verifications: &[Verification]) -> String {
+    let rows: Vec<[String; 5]> = verifications
+        .iter()
+        .map(|v| {
+            let actual = v.result.summary();
+            let (status, expected) = match &v.verdict {
+                Verdict::Pass => ("PASS", String::new()),
+                Verdict::Fail { expected } => ("FAIL", expected.clone()),
+                Verdict::Missing => ("MISSING", String::new()),
+            };
+
+            [
+                v.result.day.to_string(),
+                v.result.part.to_string(),
+                status.to_string(),
+                expected,
+                actual,
+            ]
+        })
+        .collect();
+
+    format_rows(["Day", "Part", "Status", "Expected", "Actual"], &rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::verify::*;
+    use std::time::Duration;
+
+    static ANSWERS: &str = "[day1]\n\
+        part1 = 3\n\
+        part2 = \"6\"\n\
+        \n\
+        [day2]\n\
+        part1 = 1227775554\n";
+
+    fn result(day: u32, part: u32, answer: Result<&str, &str>) -> PartResult {
+        PartResult {
+            day,
+            part,
+            answer: answer.map(str::to_string).map_err(str::to_string),
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    #[test]
+    fn parse_answers() {
+        let answers = Answers::parse(ANSWERS).unwrap();
+
+        assert_eq!(answers.get(1, 1), Some("3"));
+        assert_eq!(answers.get(1, 2), Some("6"));
+        assert_eq!(answers.get(2, 1), Some("1227775554"));
+        assert_eq!(answers.get(2, 2), None);
+    }
+
+    #[test]
+    fn parse_answers_errors() {
+        assert_eq!(
+            Answers::parse("[dayone]\npart1 = 3").unwrap_err(),
+            ParseError::new(1, 2, "expected a key like day1, found \"dayone\"")
+        );
+        assert_eq!(
+            Answers::parse("[day1]\npart = 3").unwrap_err(),
+            ParseError::new(2, 1, "expected a key like part1, found \"part\"")
+        );
+        assert_eq!(
+            Answers::parse("[day1]\npart1 = 3.5").unwrap_err(),
+            ParseError::new(2, 9, "day1.part1 should be a string or decimal integer")
+        );
+        assert_eq!(
+            Answers::parse("day1 = 3").unwrap_err(),
+            ParseError::new(1, 8, "[day1] should be a table of parts")
+        );
+        assert_eq!(Answers::parse("[day1]\npart1 = ").unwrap_err().line, 2);
+    }
+
+    #[test]
+    fn verdicts() {
+        let answers = Answers::parse(ANSWERS).unwrap();
+        let results = vec![
+            result(1, 1, Ok("3")),
+            result(1, 2, Ok("7")),
+            result(2, 1, Err("broken")),
+            result(2, 2, Ok("42")),
+            result(3, 1, Err("broken")),
+        ];
+
+        let verdicts: Vec<Verdict> = verify(&results, &answers)
+            .into_iter()
+            .map(|v| v.verdict)
+            .collect();
+
+        assert_eq!(
+            verdicts,
+            vec![
+                Verdict::Pass,
+                Verdict::Fail {
+                    expected: "6".to_string()
+                },
+                Verdict::Fail {
+                    expected: "1227775554".to_string()
+                },
+                Verdict::Missing,
+                Verdict::Fail {
+                    expected: "?".to_string()
+                },
+            ]
+        );
+    }
+}